@@ -0,0 +1,115 @@
+use crate::{generator::SeededGenerator, settings::Settings, termsweeper::Termsweeper};
+use rand::Rng;
+use std::{
+    io::{self, Write},
+    process,
+    rc::Rc,
+};
+
+const USAGE: &str = "\
+Usage: termsweeper --find-board [columns=<n>] [rows=<n>] [mines=<n>] [first=<row>,<column>]
+                                [min-opening=<n>] [max-opening=<n>] [number=<n>]
+                                [board=<hash>] [seed=<n>] [attempts=<n>]
+Board size and the other options default to the saved settings, the first reveal to (1,1).";
+
+/// What the first reveal of a board found by [`run`] has to open.
+struct Criteria {
+    /// Zero-based location of the first reveal.
+    first: (u8, u8),
+    /// Fields the first reveal opens, itself included.
+    min_opening: u16,
+    max_opening: u16,
+    /// A number that has to show in the opening.
+    number: Option<u8>,
+    /// The hash of the board, to check a board generated again from its seed.
+    board: Option<u64>,
+}
+
+impl Criteria {
+    fn matches(&self, game: &Termsweeper) -> bool {
+        let snapshot = game.snapshot();
+        let opened: Vec<&String> = snapshot
+            .iter()
+            .flatten()
+            .filter(|field| *field != "?")
+            .collect();
+        self.board
+            .is_none_or(|board| game.board_hash() == Some(board))
+            && (self.min_opening..=self.max_opening).contains(&(opened.len() as u16))
+            && self
+                .number
+                .is_none_or(|number| opened.iter().any(|field| **field == number.to_string()))
+    }
+}
+
+/// Searches for a board whose first reveal opens what the `key=value` arguments ask for,
+/// for designing fixed boards like the tutorial's. Boards are generated from consecutive
+/// seeds, the first match is written to stdout as its seed and its layout in the format
+/// of [`Termsweeper::from_layout`].
+pub fn run(arguments: &[String]) -> io::Result<()> {
+    let mut settings = Settings::load();
+    // The search does the filtering the generation options would otherwise do.
+    settings.min_opening = 0;
+    settings.min_fields_after_first_reveal = 0;
+    settings.max_flood_per_click = 0;
+    let mut criteria = Criteria {
+        first: (0, 0),
+        min_opening: 0,
+        max_opening: u16::MAX,
+        number: None,
+        board: None,
+    };
+    let mut seed: u64 = rand::thread_rng().gen();
+    let mut attempts: u64 = 10_000;
+    for argument in arguments {
+        let parsed = match argument.split_once('=') {
+            Some(("columns", value)) => value.parse().map(|value| settings.columns = value),
+            Some(("rows", value)) => value.parse().map(|value| settings.rows = value),
+            Some(("mines", value)) => value.parse().map(|value| settings.number_of_mines = value),
+            Some(("first", value)) => match value.split_once(',') {
+                Some((row, column)) => row.parse::<u8>().and_then(|row| {
+                    column.parse::<u8>().map(|column| {
+                        criteria.first = (row.saturating_sub(1), column.saturating_sub(1))
+                    })
+                }),
+                None => exit_with_usage(&format!("Expected <row>,<column> in '{argument}'")),
+            },
+            Some(("min-opening", value)) => value.parse().map(|value| criteria.min_opening = value),
+            Some(("max-opening", value)) => value.parse().map(|value| criteria.max_opening = value),
+            Some(("number", value)) => value.parse().map(|value| criteria.number = Some(value)),
+            Some(("board", value)) => {
+                u64::from_str_radix(value, 16).map(|value| criteria.board = Some(value))
+            }
+            Some(("seed", value)) => value.parse().map(|value| seed = value),
+            Some(("attempts", value)) => value.parse().map(|value| attempts = value),
+            _ => exit_with_usage(&format!("Unknown argument '{argument}'")),
+        };
+        if parsed.is_err() {
+            exit_with_usage(&format!("Expected a number in '{argument}'"));
+        }
+    }
+    let (columns, rows, _) = Termsweeper::from_settings(&settings).dimensions();
+    if criteria.first.0 >= rows || criteria.first.1 >= columns {
+        exit_with_usage(&format!(
+            "The first reveal is off the board of {rows} rows and {columns} columns"
+        ));
+    }
+    for seed in (0..attempts).map(|attempt| seed.wrapping_add(attempt)) {
+        let mut game = Termsweeper::from_settings(&settings);
+        game.set_generator(Rc::new(SeededGenerator { seed }));
+        game.reveal_at(criteria.first);
+        if criteria.matches(&game) {
+            let mut stdout = io::stdout().lock();
+            writeln!(stdout, "seed {seed}")?;
+            write!(stdout, "{}", game.layout())?;
+            return Ok(());
+        }
+    }
+    eprintln!("No board of {attempts} attempts from seed {seed} matches");
+    process::exit(1);
+}
+
+fn exit_with_usage(error: &str) -> ! {
+    eprintln!("{error}\n{USAGE}");
+    process::exit(2);
+}
@@ -0,0 +1,212 @@
+use crate::termsweeper::{self, Termsweeper};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    prelude::*,
+    symbols::border,
+    widgets::{block::*, *},
+};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+/// Where won games are kept, inside the saved games so a reset deletes them with those.
+fn directory() -> Option<PathBuf> {
+    termsweeper::games_directory().map(|directory| directory.join("gallery"))
+}
+
+/// Files in the gallery, oldest first as their names start with the time they were saved.
+fn files() -> io::Result<Vec<PathBuf>> {
+    let Some(directory) = directory() else {
+        return Ok(vec![]);
+    };
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(error) => return Err(error),
+    };
+    let mut files = vec![];
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|extension| extension == "txt") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Adds a won game to the gallery as its [`Termsweeper::result_line`] followed by the
+/// saved game, then deletes the oldest entries beyond `keep`.
+pub fn save(game: &Termsweeper, keep: u16) -> io::Result<()> {
+    let (Some(directory), Some(result), Some(hash)) =
+        (directory(), game.result_line(), game.board_hash())
+    else {
+        return Ok(());
+    };
+    let seconds = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs());
+    fs::create_dir_all(&directory)?;
+    fs::write(
+        directory.join(format!("{seconds}-{hash:016x}.txt")),
+        format!("{result}\n{}", game.export()),
+    )?;
+    let files = files()?;
+    for path in &files[..files.len().saturating_sub(keep as usize)] {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// A won game as listed in the gallery.
+struct Entry {
+    summary: String,
+    /// The mines, one line per row like [`Termsweeper::layout`].
+    layout: Vec<String>,
+}
+
+impl Entry {
+    /// Reads an entry written by [`save`], `None` for files in another format.
+    fn read(path: &Path) -> Option<Entry> {
+        let content = fs::read_to_string(path).ok()?;
+        let mut lines = content.lines();
+        let result: BTreeMap<&str, &str> = lines
+            .next()?
+            .split_whitespace()
+            .filter_map(|pair| pair.split_once('='))
+            .collect();
+        let layout = lines
+            .skip_while(|line| *line != "layout")
+            .skip(1)
+            .take_while(|line| *line != "moves")
+            .map(str::to_string)
+            .collect();
+        let time = Duration::from_millis(result.get("time_ms")?.parse().ok()?);
+        let summary = format!(
+            "{}×{} with {} mines in {}, {} clicks, board {} seed {}",
+            result.get("columns")?,
+            result.get("rows")?,
+            result.get("mines")?,
+            termsweeper::format_duration(time, true),
+            result.get("clicks")?,
+            result.get("board")?,
+            result.get("seed")?,
+        );
+        Some(Entry { summary, layout })
+    }
+}
+
+/// The won games kept in the gallery, newest first, with the mines of the selected one.
+pub struct Gallery {
+    entries: Vec<Entry>,
+    selection: usize,
+    /// Why the gallery couldn't be read.
+    error: Option<String>,
+}
+
+impl Gallery {
+    pub fn load() -> Gallery {
+        let (entries, error) = match files() {
+            Ok(files) => (
+                files
+                    .iter()
+                    .rev()
+                    .filter_map(|path| Entry::read(path))
+                    .collect(),
+                None,
+            ),
+            Err(error) => (vec![], Some(format!("Can't read the gallery: {error}"))),
+        };
+        Gallery {
+            entries,
+            selection: 0,
+            error,
+        }
+    }
+
+    pub fn handle_event(&mut self, key: KeyEvent) -> bool {
+        let entries = self.entries.len().max(1);
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selection = (self.selection + entries - 1) % entries
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.selection = (self.selection + 1) % entries,
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        let top = Title::from(" Termsweeper - Gallery ".green().bold());
+        let bottom = Title::from(Line::from(vec![
+            " Select".into(),
+            "<↑/↓> ".green().bold(),
+            "Back".into(),
+            "<Esc/E> ".green().bold(),
+        ]));
+        let mut block = Block::default()
+            .title(top.alignment(Alignment::Center))
+            .title(
+                bottom
+                    .alignment(Alignment::Center)
+                    .position(Position::Bottom),
+            )
+            .borders(Borders::ALL)
+            .border_set(border::THICK);
+        if let Some(error) = &self.error {
+            block = block.title(Title::from(format!(" {error} ").red()));
+        }
+        let inner_area = block.inner(area);
+        block.render(area, buf);
+        if self.entries.is_empty() {
+            Paragraph::new(vec![
+                Line::default(),
+                "No won games yet. Set Gallery in the settings to keep them here.".into(),
+            ])
+            .centered()
+            .render(inner_area, buf);
+            return;
+        }
+        let [list_area, preview_area] =
+            Layout::vertical([Constraint::Percentage(40), Constraint::Min(0)]).areas(inner_area);
+        // Scrolled just far enough to keep the selection in view.
+        let offset = self
+            .selection
+            .saturating_sub(list_area.height.saturating_sub(2) as usize);
+        let mut lines = vec![Line::default()];
+        for (i, entry) in self.entries.iter().enumerate().skip(offset) {
+            let line = Line::from(entry.summary.as_str());
+            lines.push(if i == self.selection {
+                line.green().reversed()
+            } else {
+                line
+            });
+        }
+        Paragraph::new(lines).centered().render(list_area, buf);
+        let preview: Vec<Line> = self.entries[self.selection]
+            .layout
+            .iter()
+            .map(|row| {
+                Line::from(
+                    row.chars()
+                        .map(|field| match field {
+                            '*' => "*".red(),
+                            _ => "·".dark_gray(),
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+        Paragraph::new(preview)
+            .centered()
+            .block(
+                Block::default()
+                    .title(Title::from(" Mines ").alignment(Alignment::Center))
+                    .borders(Borders::TOP),
+            )
+            .render(preview_area, buf);
+    }
+}
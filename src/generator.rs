@@ -0,0 +1,79 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Decides where the mines of a new board go.
+pub trait BoardGenerator {
+    /// Locations of `number_of_mines` distinct mines on a board of `columns` × `rows`
+    /// fields. None of them may be in `safe`, the first revealed field and its
+    /// neighbourhood, which is empty for boards without first-click safety.
+    fn generate(
+        &self,
+        columns: u8,
+        rows: u8,
+        number_of_mines: u16,
+        safe: &[(u8, u8)],
+    ) -> Vec<(u8, u8)>;
+}
+
+/// Spreads the mines uniformly over the fields outside the safe area. The same seed places
+/// them the same way every time, so a board can be generated again from its seed.
+pub struct SeededGenerator {
+    pub seed: u64,
+}
+
+impl BoardGenerator for SeededGenerator {
+    fn generate(
+        &self,
+        columns: u8,
+        rows: u8,
+        number_of_mines: u16,
+        safe: &[(u8, u8)],
+    ) -> Vec<(u8, u8)> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut mine_locations: Vec<(u8, u8)> = vec![];
+        while mine_locations.len() < number_of_mines as usize {
+            let location = (rng.gen_range(0..rows), rng.gen_range(0..columns));
+            if !safe.contains(&location) && !mine_locations.contains(&location) {
+                mine_locations.push(location);
+            }
+        }
+        mine_locations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn places_exactly_the_number_of_mines() {
+        for number_of_mines in [1, 40, 99] {
+            let mut mines = SeededGenerator { seed: 7 }.generate(30, 16, number_of_mines, &[]);
+            assert_eq!(mines.len(), number_of_mines as usize);
+            mines.sort();
+            mines.dedup();
+            assert_eq!(mines.len(), number_of_mines as usize);
+            assert!(mines.iter().all(|&(row, column)| row < 16 && column < 30));
+        }
+    }
+
+    #[test]
+    fn keeps_the_safe_fields_free() {
+        let safe: Vec<(u8, u8)> = (0..3)
+            .flat_map(|row| (0..3).map(move |column| (row, column)))
+            .collect();
+        for seed in 0..50 {
+            // Fills every field outside the safe ones.
+            let mines = SeededGenerator { seed }.generate(5, 5, 16, &safe);
+            assert!(mines.iter().all(|location| !safe.contains(location)));
+        }
+    }
+
+    #[test]
+    fn same_seed_places_the_same_mines() {
+        let first = SeededGenerator { seed: 42 }.generate(16, 16, 40, &[(8, 8)]);
+        let second = SeededGenerator { seed: 42 }.generate(16, 16, 40, &[(8, 8)]);
+        assert_eq!(first, second);
+        let other = SeededGenerator { seed: 43 }.generate(16, 16, 40, &[(8, 8)]);
+        assert_ne!(first, other);
+    }
+}
@@ -1,85 +1,486 @@
-use crossterm::event::{self, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{self, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
     prelude::*,
     symbols::border,
     widgets::{block::*, *},
 };
-use std::io;
+use settings::Settings;
+use stats::Stats;
+use std::{
+    fs, io,
+    time::{Duration, Instant},
+};
 
+mod designer;
+mod gallery;
+mod generator;
+mod remote;
+mod settings;
+mod solver;
+mod stats;
 mod termsweeper;
 mod tui;
+mod tutorial;
 
-static LAZY_REDRAW: bool = true;
+static FRAME_WIDTH: u16 = 120;
+static FRAME_HEIGHT: u16 = 42;
+/// Narrowest game screen fitted to its board, leaving room for the header and titles.
+static MIN_FITTED_WIDTH: u16 = 40;
+/// How long the offer of a board fitting a resized terminal stays in the status line.
+static REFIT_OFFER_DURATION: Duration = Duration::from_secs(5);
+/// How long copying a seed is confirmed in the status line.
+static COPY_CONFIRMATION_DURATION: Duration = Duration::from_secs(3);
+/// How long a failure to add a won game to the gallery stays in the status line.
+static GALLERY_ERROR_DURATION: Duration = Duration::from_secs(5);
+/// How long a failure to autosave stays in the status line.
+static AUTOSAVE_ERROR_DURATION: Duration = Duration::from_secs(5);
 static TITLE_SCREEN_CONTENT: &str = include_str!("../assets/title.in");
 fn main() -> io::Result<()> {
+    let arguments: Vec<String> = std::env::args().skip(1).collect();
+    if arguments.iter().any(|argument| argument == "--remote") {
+        return remote::run();
+    }
+    if arguments
+        .first()
+        .is_some_and(|argument| argument == "--find-board")
+    {
+        return designer::run(&arguments[1..]);
+    }
+    let mut app = TermsweeperApp::new();
+    if arguments.iter().any(|argument| argument == "--log") {
+        app.result_lines = Some(vec![]);
+    }
     let mut terminal = tui::init()?;
-    let app_result = TermsweeperApp::new().run(&mut terminal);
+    let app_result = app.run(&mut terminal);
     tui::restore()?;
+    // Printed once the terminal is restored, the game screen would hide them before.
+    for line in app.result_lines.iter().flatten().chain(&app.copied_seeds) {
+        println!("{line}");
+    }
     app_result
 }
 
+/// Keys that move the cursor on the board, the only ones acted on again while held. The
+/// settings screen only repeats the vertical ones, which move its selection, as left and
+/// right change the selected option. Ctrl combinations are never movement.
+fn is_movement(key: &KeyEvent) -> bool {
+    !key.modifiers.contains(KeyModifiers::CONTROL)
+        && matches!(
+            key.code,
+            KeyCode::Up
+                | KeyCode::Down
+                | KeyCode::Left
+                | KeyCode::Right
+                | KeyCode::Char('h' | 'j' | 'k' | 'l')
+        )
+}
+
 struct TermsweeperApp {
     exit: bool,
+    /// When the game screen changes next without input, see
+    /// [`termsweeper::Termsweeper::next_frame`].
+    redraw_at: Option<Instant>,
+    last_input: Instant,
+    terminal_size: Rect,
+    /// Set by F5 to clear the terminal and draw everything again before the next frame.
+    full_redraw: bool,
+    /// Until when F replaces a game in progress with a board that fits, after a resize or
+    /// a first F asking to confirm.
+    refit_offered_until: Option<Instant>,
+    settings: Settings,
+    settings_selection: usize,
+    /// `None` when the stats file can't be used, to avoid overwriting it.
+    stats: Option<Stats>,
+    /// Why the stats file is ignored, shown on the title screen.
+    stats_warning: Option<String>,
+    /// Why the last board import or resume failed, shown on the title screen until the
+    /// next key.
+    import_error: Option<String>,
+    /// Whether an autosaved game can be resumed with A from the title screen, offered
+    /// until a game is started.
+    autosave_available: bool,
+    /// When the current game was last autosaved, `None` to autosave it with the next input.
+    autosaved_at: Option<Instant>,
+    /// Set by X on the settings screen until the next key confirms or cancels the reset.
+    reset_pending: bool,
+    /// Outcome of the last reset, shown on the settings screen until the next key.
+    reset_result: Option<Result<(), String>>,
     app_state: termsweeper::AppState,
     game: Option<termsweeper::Termsweeper>,
+    /// The tutorial while it is open, kept apart from `game` so it never counts in the stats.
+    tutorial: Option<tutorial::Tutorial>,
+    /// The gallery while it is open, read from disk when opened.
+    gallery: Option<gallery::Gallery>,
+    /// With `--log`, a [`termsweeper::Termsweeper::result_line`] for every game finished,
+    /// printed on exit.
+    result_lines: Option<Vec<String>>,
+    /// Seeds copied with C from finished games, printed on exit for lack of a clipboard.
+    copied_seeds: Vec<String>,
 }
 
 impl TermsweeperApp {
+    /// The app on the saved settings and stats, offering to resume an autosave if there is
+    /// one.
     fn new() -> TermsweeperApp {
+        let (stats, stats_warning) = match Stats::load() {
+            Ok(stats) => (Some(stats), None),
+            Err(warning) => (None, Some(warning)),
+        };
+        TermsweeperApp {
+            stats,
+            stats_warning,
+            autosave_available: termsweeper::has_autosave(),
+            ..TermsweeperApp::with(Settings::load(), Stats::default())
+        }
+    }
+
+    /// The app on the title screen with `settings` and `stats`, without reading any files.
+    fn with(settings: Settings, stats: Stats) -> TermsweeperApp {
         TermsweeperApp {
             exit: false,
+            redraw_at: None,
+            last_input: Instant::now(),
+            terminal_size: Rect::default(),
+            full_redraw: false,
+            refit_offered_until: None,
+            settings,
+            settings_selection: 0,
+            stats: Some(stats),
+            stats_warning: None,
+            import_error: None,
+            autosave_available: false,
+            autosaved_at: None,
+            reset_pending: false,
+            reset_result: None,
             app_state: termsweeper::AppState::TitleScreen,
             game: None,
+            tutorial: None,
+            gallery: None,
+            result_lines: None,
+            copied_seeds: vec![],
         }
     }
     fn run(&mut self, terminal: &mut tui::Tui) -> io::Result<()> {
         while !self.exit {
+            self.redraw_at = self.next_frame().map(|frame| Instant::now() + frame);
+            if self.full_redraw {
+                self.full_redraw = false;
+                terminal.autoresize()?;
+                terminal.clear()?;
+                if let Some(game) = &mut self.game {
+                    game.invalidate_board();
+                }
+                if let Some(tutorial) = &mut self.tutorial {
+                    tutorial.game_mut().invalidate_board();
+                }
+            }
+            let terminal_size = terminal.size()?;
+            if terminal_size != self.terminal_size {
+                self.terminal_size = terminal_size;
+                self.offer_fitting_board();
+            }
             terminal.draw(|frame| self.render_frame(frame))?;
             self.handle_events()?;
         }
+        // Whatever happened since the last autosave is kept on the way out.
+        self.autosaved_at = None;
+        self.autosave();
         Ok(())
     }
 
     fn render_frame(&self, frame: &mut Frame) {
+        frame.render_widget(self, Self::frame_area(frame.size(), self.frame_size()));
+    }
+
+    /// The centered area of the screens, as far as the terminal allows.
+    fn frame_area(terminal: Rect, (width, height): (u16, u16)) -> Rect {
         let horizontal_layout = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([
                 Constraint::Min(0),
-                Constraint::Length(120),
+                Constraint::Length(width),
                 Constraint::Min(0),
             ])
-            .split(frame.size());
+            .split(terminal);
         let vertical_layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Min(0),
-                Constraint::Length(42),
+                Constraint::Length(height),
                 Constraint::Min(0),
             ])
             .split(horizontal_layout[1]);
-        frame.render_widget(self, vertical_layout[1]);
+        vertical_layout[1]
+    }
+
+    fn frame_size(&self) -> (u16, u16) {
+        match (&self.app_state, &self.game) {
+            (termsweeper::AppState::GameScreen, Some(game)) => {
+                self.game_frame_size(game.required_width(), game.required_height())
+            }
+            _ => (FRAME_WIDTH, FRAME_HEIGHT),
+        }
+    }
+
+    /// Size of the game screen for a board needing `width` × `height` characters: the fixed
+    /// frame grown to fit it, or exactly its size with equal gutters around it.
+    fn game_frame_size(&self, width: u16, height: u16) -> (u16, u16) {
+        if self.settings.fit_frame_to_board {
+            (width.max(MIN_FITTED_WIDTH), height)
+        } else {
+            (FRAME_WIDTH.max(width), FRAME_HEIGHT.max(height))
+        }
     }
 
     fn handle_events(&mut self) -> io::Result<()> {
         loop {
-            if event::poll(std::time::Duration::from_millis(16))? {
-                if let event::Event::Key(key) = event::read()? {
-                    let event_handled = match self.app_state {
-                        termsweeper::AppState::TitleScreen => self.handle_title_screen(key),
-                        termsweeper::AppState::GameScreen => self.handle_game_screen(key),
-                    };
-                    if event_handled
-                        || (key.kind == KeyEventKind::Press && key.code == KeyCode::F(5))
-                    {
-                        break;
-                    }
+            let was_finished = self.game_finished();
+            let was_over = self.game_over();
+            let handled = self.handle_event()?;
+            if !was_finished && self.game_finished() {
+                self.record_game_result();
+            }
+            if !was_over && self.game_over() {
+                self.log_game_result();
+                self.save_to_gallery();
+            }
+            self.autosave();
+            if handled {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether handlers act on `key`: presses, and repeats of held movement keys so the
+    /// cursor glides across the board. Repeated reveals, flags, option changes or quits
+    /// would be accidents, and releases would trigger every action twice. The title screen
+    /// has no movement, its H starts a hardcore game.
+    fn accepts(&self, key: &KeyEvent) -> bool {
+        let repeats = match self.app_state {
+            termsweeper::AppState::TitleScreen => false,
+            termsweeper::AppState::Settings => {
+                is_movement(key)
+                    && matches!(
+                        key.code,
+                        KeyCode::Up | KeyCode::Down | KeyCode::Char('j' | 'k')
+                    )
+            }
+            _ => is_movement(key),
+        };
+        key.kind == KeyEventKind::Press || key.kind == KeyEventKind::Repeat && repeats
+    }
+
+    /// Waits for input or the next timed update, returns whether a redraw is due.
+    fn handle_event(&mut self) -> io::Result<bool> {
+        let input_ready = match self.poll_timeout() {
+            Some(timeout) => event::poll(timeout)?,
+            None => true,
+        };
+        if input_ready {
+            let event = event::read()?;
+            // Redraw for the new size right away, which also notices when a board fitted
+            // to the terminal no longer fits.
+            if matches!(event, event::Event::Resize(..)) {
+                return Ok(true);
+            }
+            if let event::Event::Key(key) = event {
+                if !self.accepts(&key) {
+                    return Ok(false);
+                }
+                self.last_input = Instant::now();
+                // Raw mode keeps Ctrl+C from interrupting the process, so it quits like Q
+                // from every screen, restoring the terminal on the way out.
+                if key.modifiers.contains(KeyModifiers::CONTROL)
+                    && matches!(key.code, KeyCode::Char('c') | KeyCode::Char('q'))
+                {
+                    self.exit = true;
+                    return Ok(true);
+                }
+                if self.resume_game() {
+                    return Ok(true);
                 }
-                if !LAZY_REDRAW {
-                    break;
+                if key.code == KeyCode::F(5) {
+                    self.full_redraw = true;
+                    return Ok(true);
+                }
+                let event_handled = match self.app_state {
+                    termsweeper::AppState::TitleScreen => self.handle_title_screen(key),
+                    termsweeper::AppState::GameScreen => self.handle_game_screen(key),
+                    termsweeper::AppState::Settings => self.handle_settings_screen(key),
+                    termsweeper::AppState::Tutorial => self.handle_tutorial(key),
+                    termsweeper::AppState::Gallery => self.handle_gallery(key),
+                };
+                if event_handled {
+                    return Ok(true);
                 }
             }
+            Ok(!self.settings.lazy_redraw)
+        } else {
+            let frame_due = self.redraw_at.is_some_and(|at| at <= Instant::now());
+            Ok(self.pause_idle_game() || self.dwell() || frame_due)
         }
-        Ok(())
+    }
+
+    /// Whether the current game has ended with a result for the stats.
+    fn game_finished(&self) -> bool {
+        self.game
+            .as_ref()
+            .is_some_and(|game| game.result().is_some())
+    }
+
+    /// Whether the current game has ended, counting for the stats or not.
+    fn game_over(&self) -> bool {
+        self.game.as_ref().is_some_and(|game| game.is_finished())
+    }
+
+    fn log_game_result(&mut self) {
+        if let (Some(lines), Some(line)) = (
+            &mut self.result_lines,
+            self.game.as_ref().and_then(|game| game.result_line()),
+        ) {
+            lines.push(line);
+        }
+    }
+
+    fn save_to_gallery(&mut self) {
+        let keep = self.settings.gallery_size;
+        // Only wins that count for the stats, assisted ones are no feat to keep.
+        let counted_win =
+            |game: &termsweeper::Termsweeper| game.result().is_some_and(|result| result.won);
+        let Some(game) = self
+            .game
+            .as_mut()
+            .filter(|game| keep > 0 && counted_win(game))
+        else {
+            return;
+        };
+        if let Err(error) = gallery::save(game, keep) {
+            game.set_status(
+                format!("Can't add the game to the gallery: {error}"),
+                Color::LightRed,
+                GALLERY_ERROR_DURATION,
+            );
+        }
+    }
+
+    /// Autosaves the current game once the interval has passed since the last time, or
+    /// deletes the autosave once there is nothing to resume, see
+    /// [`termsweeper::Termsweeper::autosave`].
+    fn autosave(&mut self) {
+        let interval = Duration::from_secs(self.settings.autosave_seconds.into());
+        let (Some(game), false) = (
+            &mut self.game,
+            interval.is_zero()
+                || self
+                    .autosaved_at
+                    .is_some_and(|autosaved_at| autosaved_at.elapsed() < interval),
+        ) else {
+            return;
+        };
+        self.autosaved_at = Some(Instant::now());
+        let result = match game.autosave() {
+            Some(text) => termsweeper::write_autosave(&text),
+            None => termsweeper::delete_autosave(),
+        };
+        if let Err(error) = result {
+            game.set_status(
+                format!("Can't autosave the game: {error}"),
+                Color::LightRed,
+                AUTOSAVE_ERROR_DURATION,
+            );
+        }
+    }
+
+    fn record_game_result(&mut self) {
+        if let (Some(stats), Some(result)) = (
+            &mut self.stats,
+            self.game.as_ref().and_then(|game| game.result()),
+        ) {
+            stats.record(result);
+            // Like the settings, failing to write only loses the stats for the next run.
+            let _ = stats.save();
+        }
+    }
+
+    /// Pauses the running game once the player has been idle for too long.
+    fn pause_idle_game(&mut self) -> bool {
+        match (
+            self.settings.idle_pause_seconds,
+            &self.app_state,
+            &mut self.game,
+        ) {
+            (threshold @ 1.., termsweeper::AppState::GameScreen, Some(game))
+                if self.last_input.elapsed() >= Duration::from_secs(threshold)
+                    && !game.is_paused() =>
+            {
+                game.pause(self.settings.idle_pause_obscures_board);
+                game.is_paused()
+            }
+            _ => false,
+        }
+    }
+
+    /// Reveals the field under a resting cursor when dwell reveals are enabled.
+    fn dwell(&mut self) -> bool {
+        match (
+            self.settings.dwell_reveal_millis,
+            &self.app_state,
+            &mut self.game,
+        ) {
+            (threshold @ 1.., termsweeper::AppState::GameScreen, Some(game)) => {
+                game.dwell(Duration::from_millis(threshold))
+            }
+            _ => false,
+        }
+    }
+
+    /// Resumes a paused game, swallowing the key press that woke it up.
+    fn resume_game(&mut self) -> bool {
+        match (&self.app_state, &mut self.game) {
+            (termsweeper::AppState::GameScreen, Some(game)) if game.is_paused() => {
+                game.resume();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn next_frame(&self) -> Option<Duration> {
+        match (&self.app_state, &self.game) {
+            (termsweeper::AppState::GameScreen, Some(game)) => game.next_frame(),
+            (termsweeper::AppState::Tutorial, _) => self
+                .tutorial
+                .as_ref()
+                .and_then(|tutorial| tutorial.game().next_frame()),
+            _ => None,
+        }
+    }
+
+    /// How long to wait for input: until the next frame, idle pause or dwell reveal is due
+    /// and at most the idle polling interval. `None` waits for input indefinitely.
+    fn poll_timeout(&self) -> Option<Duration> {
+        let mut deadlines = vec![];
+        deadlines.extend(self.redraw_at);
+        if let (termsweeper::AppState::GameScreen, Some(game)) = (&self.app_state, &self.game) {
+            if self.settings.idle_pause_seconds > 0 && game.is_timer_running() {
+                deadlines
+                    .push(self.last_input + Duration::from_secs(self.settings.idle_pause_seconds));
+            }
+            if self.settings.dwell_reveal_millis > 0 {
+                deadlines.extend(
+                    game.dwell_deadline(Duration::from_millis(self.settings.dwell_reveal_millis)),
+                );
+            }
+        }
+        let now = Instant::now();
+        let idle_poll = (self.settings.idle_poll_millis > 0)
+            .then(|| Duration::from_millis(self.settings.idle_poll_millis));
+        deadlines
+            .into_iter()
+            .map(|deadline| deadline.saturating_duration_since(now))
+            .chain(idle_poll)
+            .min()
     }
 
     fn render_title_screen(&self, area: Rect, buf: &mut Buffer) {
@@ -87,11 +488,25 @@ impl TermsweeperApp {
         let bottom = Title::from(Line::from(vec![
             " New Game".into(),
             "<N> ".green().bold(),
+            "Hardcore".into(),
+            "<H> ".green().bold(),
+            "Zen".into(),
+            "<Z> ".green().bold(),
+            "Import".into(),
+            "<I> ".green().bold(),
+            "Tutorial".into(),
+            "<T> ".green().bold(),
+            "Settings".into(),
+            "<S> ".green().bold(),
+            "Gallery".into(),
+            "<G> ".green().bold(),
+            "Redraw".into(),
+            "<F5> ".green().bold(),
             "Quit".into(),
             "<Q> ".green().bold(),
         ]));
 
-        let block = Block::default()
+        let mut block = Block::default()
             .title(top.alignment(Alignment::Center))
             .title(
                 bottom
@@ -100,6 +515,39 @@ impl TermsweeperApp {
             )
             .borders(Borders::ALL)
             .border_set(border::THICK);
+        if let Some(warning) = &self.stats_warning {
+            block = block.title(Title::from(format!(" {warning} ").yellow()));
+        }
+        if let Some(error) = &self.import_error {
+            block = block.title(Title::from(format!(" {error} ").red()));
+        }
+        if self.autosave_available {
+            block = block.title(Title::from(Line::from(vec![
+                " An unfinished game was autosaved, resume it".yellow(),
+                "<A> ".green().bold(),
+            ])));
+        }
+        if let Some(stats) = &self.stats {
+            let mut summary = format!(
+                " Won {} of {}",
+                termsweeper::format_count(stats.games_won.into()),
+                termsweeper::format_count(stats.games_played.into())
+            );
+            let board = stats::board_key(
+                self.settings.columns,
+                self.settings.rows,
+                self.settings.number_of_mines,
+                self.settings.neighbor_radius,
+            );
+            let best = stats.best_time(&board).map_or("-".to_string(), |best| {
+                termsweeper::format_duration(best, true)
+            });
+            let clicks = stats
+                .best_clicks(&board)
+                .map_or("-".to_string(), |clicks| clicks.to_string());
+            summary += &format!(" - best {best} and {clicks} clicks on {board}");
+            block = block.title(Title::from(summary + " ").alignment(Alignment::Right));
+        }
         Paragraph::new(TITLE_SCREEN_CONTENT)
             .centered()
             .block(block)
@@ -107,18 +555,317 @@ impl TermsweeperApp {
     }
 
     fn handle_title_screen(&mut self, key: KeyEvent) -> bool {
-        if key.kind == KeyEventKind::Press {
-            match key.code {
-                KeyCode::Char('n') => {
-                    self.app_state = termsweeper::AppState::GameScreen;
-                    self.game = Some(termsweeper::Termsweeper::default());
+        self.import_error = None;
+        match key.code {
+            KeyCode::Char('n') => self.start_game(termsweeper::Termsweeper::from_settings(
+                &self.game_settings(),
+            )),
+            KeyCode::Char('h') => {
+                self.start_game(termsweeper::Termsweeper::hardcore(&self.game_settings()))
+            }
+            KeyCode::Char('z') => {
+                self.start_game(termsweeper::Termsweeper::zen(&self.game_settings()))
+            }
+            KeyCode::Char('i') => self.import_board(),
+            KeyCode::Char('a') if self.autosave_available => self.resume_autosave(),
+            KeyCode::Char('t') => self.start_tutorial(),
+            KeyCode::Char('s') => self.app_state = termsweeper::AppState::Settings,
+            KeyCode::Char('g') => {
+                self.gallery = Some(gallery::Gallery::load());
+                self.app_state = termsweeper::AppState::Gallery;
+            }
+            KeyCode::Char('q') => self.exit = true,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Starts a game on the board laid out in `board.txt` next to the config file, see
+    /// [`termsweeper::Termsweeper::from_layout`].
+    fn import_board(&mut self) {
+        let Some(path) =
+            dirs::config_dir().map(|directory| directory.join("termsweeper").join("board.txt"))
+        else {
+            self.import_error = Some("No config directory to import a board from".to_string());
+            return;
+        };
+        let game = fs::read_to_string(&path)
+            .map_err(|error| format!("Can't read {}: {error}", path.display()))
+            .and_then(|layout| termsweeper::Termsweeper::from_layout(&layout, &self.settings));
+        match game {
+            Ok(game) => self.start_game(game),
+            Err(error) => self.import_error = Some(error),
+        }
+    }
+
+    /// Carries on with the autosaved game, see [`termsweeper::Termsweeper::from_autosave`].
+    fn resume_autosave(&mut self) {
+        let game = termsweeper::read_autosave()
+            .map_err(|error| format!("Can't read the autosave: {error}"))
+            .and_then(|text| termsweeper::Termsweeper::from_autosave(&text, &self.settings));
+        match game {
+            Ok(game) => {
+                // With autosaving off the autosave would never be replaced, and offered
+                // again after this game is long over.
+                if self.settings.autosave_seconds == 0 {
+                    let _ = termsweeper::delete_autosave();
                 }
-                KeyCode::Char('q') => self.exit = true,
-                _ => return false,
+                self.start_game(game);
+            }
+            Err(error) => self.import_error = Some(error),
+        }
+    }
+
+    /// The settings for a new game, with the board sized to the terminal when the
+    /// difficulty says so.
+    fn game_settings(&self) -> Settings {
+        if !self.settings.auto_fit {
+            return self.settings.clone();
+        }
+        let (columns, rows, number_of_mines) = self.fitting_size();
+        Settings {
+            columns,
+            rows,
+            number_of_mines,
+            ..self.settings.clone()
+        }
+    }
+
+    /// See [`termsweeper::Termsweeper::fitting_size`].
+    fn fitting_size(&self) -> (u8, u8, u16) {
+        termsweeper::Termsweeper::fitting_size(
+            &self.settings,
+            self.terminal_size.width,
+            self.terminal_size.height,
+        )
+    }
+
+    /// A new board after `game`, in its mode and sized like it or to the terminal.
+    fn next_board(&self, game: &termsweeper::Termsweeper) -> termsweeper::Termsweeper {
+        if self.settings.auto_fit {
+            let (columns, rows, number_of_mines) = self.fitting_size();
+            game.resized(columns, rows, number_of_mines)
+        } else {
+            game.restart(false)
+        }
+    }
+
+    /// Offers a new board that fits after the terminal was resized under a fitted one.
+    fn offer_fitting_board(&mut self) {
+        let fitting_size = self.fitting_size();
+        match (&self.app_state, &mut self.game) {
+            (termsweeper::AppState::GameScreen, Some(game))
+                if self.settings.auto_fit && game.dimensions() != fitting_size =>
+            {
+                game.set_status(
+                    "The terminal was resized, press F for a board that fits",
+                    Color::Yellow,
+                    REFIT_OFFER_DURATION,
+                );
+                self.refit_offered_until = Some(Instant::now() + REFIT_OFFER_DURATION);
+            }
+            _ => (),
+        }
+    }
+
+    fn start_game(&mut self, mut game: termsweeper::Termsweeper) {
+        let last_used = self.game.as_ref().map(|game| game.cursor_position());
+        game.place_cursor(self.settings.start_position, last_used);
+        self.app_state = termsweeper::AppState::GameScreen;
+        self.game = Some(game);
+        self.refit_offered_until = None;
+        // A new game replaces the autosaved one with its own.
+        self.autosave_available = false;
+        self.autosaved_at = None;
+    }
+
+    fn start_tutorial(&mut self) {
+        self.tutorial = Some(tutorial::Tutorial::new(&self.settings));
+        self.app_state = termsweeper::AppState::Tutorial;
+    }
+
+    fn render_settings_screen(&self, area: Rect, buf: &mut Buffer) {
+        let top = Title::from(" Termsweeper - Settings ".green().bold());
+        let bottom = Title::from(Line::from(vec![
+            " Select".into(),
+            "<↑/↓> ".green().bold(),
+            "Change".into(),
+            "<←/→> ".green().bold(),
+            "Reset data".into(),
+            "<X> ".green().bold(),
+            "Back".into(),
+            "<Esc/E> ".green().bold(),
+        ]));
+
+        let mut block = Block::default()
+            .title(top.alignment(Alignment::Center))
+            .title(
+                bottom
+                    .alignment(Alignment::Center)
+                    .position(Position::Bottom),
+            )
+            .borders(Borders::ALL)
+            .border_set(border::THICK);
+        if self.reset_pending {
+            block = block.title(Title::from(
+                " Delete all settings, stats and saved games? Y to confirm, any other key \
+                 cancels "
+                    .red()
+                    .bold(),
+            ));
+        }
+        match &self.reset_result {
+            Some(Ok(())) => {
+                block = block.title(Title::from(
+                    " Settings, stats and saved games were reset ".yellow(),
+                ))
+            }
+            Some(Err(error)) => block = block.title(Title::from(format!(" {error} ").red())),
+            None => (),
+        }
+        let mut lines = vec![Line::default()];
+        for (i, (label, value)) in self.settings.entries().into_iter().enumerate() {
+            let line = Line::from(vec![format!("{label}: ").into(), value.bold()]);
+            lines.push(if i == self.settings_selection {
+                line.green().reversed()
+            } else {
+                line
+            });
+        }
+        let inner_area = block.inner(area);
+        block.render(area, buf);
+        let [list_area, preview_area] = Layout::vertical([
+            Constraint::Length(lines.len() as u16 + 1),
+            Constraint::Min(0),
+        ])
+        .areas(inner_area);
+        Paragraph::new(lines).centered().render(list_area, buf);
+        self.render_board_preview(preview_area, buf);
+    }
+
+    /// Empty board with the configured dimensions, shrunk to a dot per field when it is
+    /// larger than the preview, and a warning when it won't fit the terminal.
+    fn render_board_preview(&self, area: Rect, buf: &mut Buffer) {
+        let preview = termsweeper::Termsweeper::from_settings(&self.game_settings());
+        let (width, height) = (preview.required_width(), preview.required_height());
+        let game_area = Self::frame_area(self.terminal_size, self.game_frame_size(width, height));
+        let title = if width <= game_area.width && height <= game_area.height {
+            let (columns, rows, _) = preview.dimensions();
+            Title::from(format!(" Preview {columns}×{rows} "))
+        } else {
+            Title::from(
+                format!(
+                    " The board needs {width}×{height} characters but only {}×{} fit the \
+                     terminal ",
+                    game_area.width, game_area.height
+                )
+                .yellow()
+                .bold(),
+            )
+        };
+        let block = Block::default()
+            .title(title.alignment(Alignment::Center))
+            .borders(Borders::TOP)
+            .border_style(Style::new().dark_gray());
+        let board_area = block.inner(area);
+        block.render(area, buf);
+        // Borders of the game screen and the status line aren't part of the preview.
+        if width - 2 <= board_area.width && height - 3 <= board_area.height {
+            preview.render_playing_board(board_area, buf);
+        } else {
+            let row = "·".repeat(self.settings.columns as usize);
+            let lines = vec![Line::from(row); self.settings.rows as usize];
+            let [_, grid_area, _] = Layout::vertical([
+                Constraint::Min(0),
+                Constraint::Length(self.settings.rows as u16),
+                Constraint::Min(0),
+            ])
+            .areas(board_area);
+            Paragraph::new(lines)
+                .centered()
+                .dark_gray()
+                .render(grid_area, buf);
+        }
+    }
+
+    fn handle_settings_screen(&mut self, key: KeyEvent) -> bool {
+        self.reset_result = None;
+        if self.reset_pending {
+            self.reset_pending = false;
+            if key.code == KeyCode::Char('y') {
+                self.reset_data();
             }
             return true;
         }
-        false
+        let entries = self.settings.entries().len();
+        match key.code {
+            KeyCode::Char('x') => self.reset_pending = true,
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.settings_selection = (self.settings_selection + entries - 1) % entries
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.settings_selection = (self.settings_selection + 1) % entries
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.settings.cycle(self.settings_selection, false)
+            }
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Enter | KeyCode::Char(' ') => {
+                self.settings.cycle(self.settings_selection, true)
+            }
+            KeyCode::Esc | KeyCode::Char('e') => {
+                // Failing to write the config only loses the settings for the next run.
+                let _ = self.settings.save();
+                if let Some(game) = &mut self.game {
+                    game.apply_settings(&self.settings);
+                }
+                self.app_state = termsweeper::AppState::TitleScreen;
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Deletes the config and stats files and the saved games, going on with defaults. A
+    /// file that can't be deleted keeps its data in memory too, the others are reset anyway.
+    /// Deletes the settings, stats and saved games, each on its own so a failure keeps the
+    /// others. The result names what was reset and what couldn't be deleted.
+    fn reset_data(&mut self) {
+        let mut reset = vec![];
+        let mut errors = vec![];
+        match Settings::delete_file() {
+            Ok(()) => {
+                self.settings = Settings::default();
+                self.settings_selection = 0;
+                reset.push("settings");
+            }
+            Err(error) => errors.push(format!("can't delete the settings: {error}")),
+        }
+        match Stats::delete_file() {
+            Ok(()) => {
+                self.stats = Some(Stats::default());
+                self.stats_warning = None;
+                reset.push("stats");
+            }
+            Err(error) => errors.push(format!("can't delete the stats: {error}")),
+        }
+        match termsweeper::delete_saved_games() {
+            Ok(()) => {
+                self.autosave_available = false;
+                reset.push("saved games");
+            }
+            Err(error) => errors.push(format!("can't delete the saved games: {error}")),
+        }
+        self.reset_result = Some(if errors.is_empty() {
+            Ok(())
+        } else if reset.is_empty() {
+            Err(format!("Nothing was reset, {}", errors.join(", ")))
+        } else {
+            Err(format!(
+                "Reset the {}, {}",
+                reset.join(" and "),
+                errors.join(", ")
+            ))
+        });
     }
 
     fn handle_game_screen(&mut self, key: KeyEvent) -> bool {
@@ -129,19 +876,96 @@ impl TermsweeperApp {
         if handled {
             return handled;
         }
-        if key.kind == KeyEventKind::Press {
-            match key.code {
-                KeyCode::Char('q') => {
-                    self.exit = true;
-                }
-                KeyCode::Char('e') => {
-                    self.app_state = termsweeper::AppState::TitleScreen;
+        if let (KeyCode::Char('w'), Some(game)) = (key.code, &mut self.game) {
+            if game.is_finished() {
+                game.save_game();
+                return true;
+            }
+        }
+        if let (KeyCode::Char('c'), Some(game)) = (key.code, &mut self.game) {
+            if let Some(line) = game.seed_line() {
+                game.set_status(
+                    "The seed will be printed when Termsweeper exits",
+                    Color::LightGreen,
+                    COPY_CONFIRMATION_DURATION,
+                );
+                if !self.copied_seeds.contains(&line) {
+                    self.copied_seeds.push(line);
                 }
-                _ => return false,
+                return true;
+            }
+        }
+        if let (KeyCode::Char('f'), true, Some(game)) =
+            (key.code, self.settings.auto_fit, &self.game)
+        {
+            let offered = self
+                .refit_offered_until
+                .is_some_and(|until| Instant::now() < until);
+            if offered || game.is_finished() || !game.is_started() {
+                self.start_game(self.next_board(game));
+            } else if let Some(game) = &mut self.game {
+                // A stray F mustn't throw the game away, a second one confirms.
+                game.set_status(
+                    "Press F again to give up this game for a board that fits",
+                    Color::Yellow,
+                    REFIT_OFFER_DURATION,
+                );
+                self.refit_offered_until = Some(Instant::now() + REFIT_OFFER_DURATION);
+            }
+            return true;
+        }
+        let finished_game = self.game.as_ref().filter(|game| game.is_finished());
+        match (key.code, finished_game) {
+            (KeyCode::Char('q'), _) => {
+                self.exit = true;
             }
+            (KeyCode::Char('e'), _) => {
+                self.app_state = termsweeper::AppState::TitleScreen;
+            }
+            (KeyCode::Char('r'), Some(game)) => self.start_game(game.restart(true)),
+            (KeyCode::Char('n'), Some(game)) => self.start_game(self.next_board(game)),
+            _ => return false,
+        }
+        true
+    }
+
+    fn handle_tutorial(&mut self, key: KeyEvent) -> bool {
+        let Some(tutorial) = &mut self.tutorial else {
+            return false;
+        };
+        if tutorial.handle_event(key) {
+            return true;
+        }
+        let finished = tutorial.game().is_finished();
+        match key.code {
+            KeyCode::Char('q') => self.exit = true,
+            KeyCode::Char('e') => {
+                self.tutorial = None;
+                self.app_state = termsweeper::AppState::TitleScreen;
+            }
+            KeyCode::Char('r') if finished => self.start_tutorial(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn handle_gallery(&mut self, key: KeyEvent) -> bool {
+        if self
+            .gallery
+            .as_mut()
+            .is_some_and(|gallery| gallery.handle_event(key))
+        {
             return true;
         }
-        false
+        match key.code {
+            KeyCode::Char('q') => self.exit = true,
+            KeyCode::Esc | KeyCode::Char('e') => {
+                self.gallery = None;
+                self.app_state = termsweeper::AppState::TitleScreen;
+            }
+            _ => return false,
+        }
+        true
     }
 }
 
@@ -149,12 +973,111 @@ impl Widget for &TermsweeperApp {
     fn render(self, area: Rect, buf: &mut Buffer) {
         match self.app_state {
             termsweeper::AppState::TitleScreen => self.render_title_screen(area, buf),
+            termsweeper::AppState::Settings => self.render_settings_screen(area, buf),
             termsweeper::AppState::GameScreen => {
-                match &self.game {
-                    Some(game) => game.render_game_screen(area, buf),
-                    None => (),
+                if let Some(game) = &self.game {
+                    game.render_game_screen(area, buf)
+                }
+            }
+            termsweeper::AppState::Tutorial => {
+                if let Some(tutorial) = &self.tutorial {
+                    tutorial.render(area, buf)
                 }
             }
+            termsweeper::AppState::Gallery => {
+                if let Some(gallery) = &self.gallery {
+                    gallery.render(area, buf)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+
+    /// The screen of `app` rendered into a [`TestBackend`] of the default frame size.
+    fn render(app: &TermsweeperApp) -> Buffer {
+        let backend = TestBackend::new(FRAME_WIDTH, FRAME_HEIGHT);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|frame| app.render_title_screen(frame.size(), frame.buffer_mut()))
+            .unwrap();
+        terminal.backend().buffer().clone()
+    }
+
+    /// The symbols on row `y` of `buffer`.
+    fn line(buffer: &Buffer, y: u16) -> String {
+        (buffer.area.left()..buffer.area.right())
+            .map(|x| buffer.get(x, y).symbol())
+            .collect()
+    }
+
+    #[test]
+    fn renders_the_title_screen() {
+        let buffer = render(&TermsweeperApp::with(Settings::default(), Stats::default()));
+        assert!(line(&buffer, 0).contains(" Termsweeper - Title Screen "));
+        let keys = line(&buffer, FRAME_HEIGHT - 1);
+        assert!(keys.contains("New Game<N>"));
+        assert!(keys.contains("Quit<Q>"));
+        assert_eq!(buffer.get(0, 0).symbol(), "┏");
+    }
+
+    fn key(code: KeyCode, kind: KeyEventKind) -> KeyEvent {
+        KeyEvent::new_with_kind(code, KeyModifiers::NONE, kind)
+    }
+
+    #[test]
+    fn acts_on_repeats_of_movement_keys_only() {
+        let mut app = TermsweeperApp::with(Settings::default(), Stats::default());
+        app.app_state = termsweeper::AppState::GameScreen;
+        for code in [KeyCode::Up, KeyCode::Right, KeyCode::Char('j')] {
+            assert!(app.accepts(&key(code, KeyEventKind::Press)));
+            assert!(app.accepts(&key(code, KeyEventKind::Repeat)));
+            assert!(!app.accepts(&key(code, KeyEventKind::Release)));
+        }
+        for code in [KeyCode::Char(' '), KeyCode::Char('m'), KeyCode::Char('q')] {
+            assert!(app.accepts(&key(code, KeyEventKind::Press)));
+            assert!(!app.accepts(&key(code, KeyEventKind::Repeat)));
+            assert!(!app.accepts(&key(code, KeyEventKind::Release)));
         }
+        let ctrl_j = KeyEvent::new_with_kind(
+            KeyCode::Char('j'),
+            KeyModifiers::CONTROL,
+            KeyEventKind::Repeat,
+        );
+        assert!(!app.accepts(&ctrl_j));
+    }
+
+    #[test]
+    fn settings_screen_repeats_only_moving_the_selection() {
+        let mut app = TermsweeperApp::with(Settings::default(), Stats::default());
+        app.app_state = termsweeper::AppState::Settings;
+        for code in [
+            KeyCode::Up,
+            KeyCode::Down,
+            KeyCode::Char('j'),
+            KeyCode::Char('k'),
+        ] {
+            assert!(app.accepts(&key(code, KeyEventKind::Repeat)));
+        }
+        for code in [
+            KeyCode::Left,
+            KeyCode::Right,
+            KeyCode::Char('h'),
+            KeyCode::Char('l'),
+        ] {
+            assert!(!app.accepts(&key(code, KeyEventKind::Repeat)));
+            assert!(app.accepts(&key(code, KeyEventKind::Press)));
+        }
+    }
+
+    #[test]
+    fn title_screen_acts_on_no_repeats() {
+        let app = TermsweeperApp::with(Settings::default(), Stats::default());
+        assert!(!app.accepts(&key(KeyCode::Char('h'), KeyEventKind::Repeat)));
+        assert!(app.accepts(&key(KeyCode::Char('h'), KeyEventKind::Press)));
     }
 }
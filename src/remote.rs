@@ -0,0 +1,207 @@
+use crate::{
+    settings::Settings,
+    termsweeper::{Outcome, Termsweeper},
+};
+use serde::Serialize;
+use std::io::{self, BufRead, Write};
+
+/// Reply to a single command, written as one line of JSON.
+#[derive(Serialize)]
+struct Response {
+    ok: bool,
+    /// Why the command was refused, only set when `ok` is false.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    state: State,
+}
+
+/// The game as a driver sees it after a command.
+#[derive(Serialize)]
+struct State {
+    columns: u8,
+    rows: u8,
+    mines: u16,
+    /// `in-progress`, `won` or `lost`.
+    outcome: &'static str,
+    /// 1-based `[row, column]`, like the coordinates of the commands.
+    cursor: [u8; 2],
+    /// One list per row, see [`Termsweeper::snapshot`].
+    board: Vec<Vec<String>>,
+}
+
+/// Plays games on the saved settings without the terminal interface, reading one command
+/// per line from stdin and answering each with a line of JSON on stdout until `quit` or
+/// the end of the input. The protocol is described in the README.
+pub fn run() -> io::Result<()> {
+    let settings = Settings::load();
+    let mut game = Termsweeper::from_settings(&settings);
+    let mut stdout = io::stdout().lock();
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if words.is_empty() {
+            continue;
+        }
+        if words == ["quit"] {
+            break;
+        }
+        let response = respond(&mut game, &settings, &words);
+        let json = serde_json::to_string(&response).map_err(io::Error::other)?;
+        writeln!(stdout, "{json}")?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
+
+/// Runs the command in `words` and describes the game after it.
+fn respond(game: &mut Termsweeper, settings: &Settings, words: &[&str]) -> Response {
+    let error = execute(game, settings, words).err();
+    Response {
+        ok: error.is_none(),
+        error,
+        state: state(game),
+    }
+}
+
+fn execute(game: &mut Termsweeper, settings: &Settings, words: &[&str]) -> Result<(), String> {
+    match words {
+        ["new"] => *game = Termsweeper::from_settings(settings),
+        ["state"] => (),
+        ["move", row, column] => {
+            let (row, column) = location(game, row, column)?;
+            game.set_cursor(row, column);
+        }
+        ["reveal" | "flag", rest @ ..] => {
+            let location = match rest {
+                [] => game.cursor_position(),
+                [row, column] => location(game, row, column)?,
+                _ => return Err(format!("Usage: {} [<row> <column>]", words[0])),
+            };
+            if game.is_finished() {
+                return Err("The game is over, start a new one with new".to_string());
+            }
+            let acted = if words[0] == "reveal" {
+                game.reveal_at(location)
+            } else {
+                game.mark_at(location)
+            };
+            // Like a revealed field, a flag over the limit or a mine zen mode keeps covered.
+            if !acted {
+                return Err(format!(
+                    "{} ({},{}) changed nothing",
+                    if words[0] == "reveal" {
+                        "Revealing"
+                    } else {
+                        "Flagging"
+                    },
+                    location.0 + 1,
+                    location.1 + 1
+                ));
+            }
+        }
+        _ => return Err(format!("Unknown command '{}'", words.join(" "))),
+    }
+    Ok(())
+}
+
+/// Parses 1-based coordinates into a zero-based location on the board.
+fn location(game: &Termsweeper, row: &str, column: &str) -> Result<(u8, u8), String> {
+    let (columns, rows, _) = game.dimensions();
+    let parse = |value: &str, limit: u8| {
+        value
+            .parse::<u8>()
+            .ok()
+            .filter(|value| (1..=limit).contains(value))
+            .map(|value| value - 1)
+    };
+    match (parse(row, rows), parse(column, columns)) {
+        (Some(row), Some(column)) => Ok((row, column)),
+        _ => Err(format!(
+            "({row},{column}) is not on the board of {rows} rows and {columns} columns"
+        )),
+    }
+}
+
+fn state(game: &Termsweeper) -> State {
+    let (columns, rows, mines) = game.dimensions();
+    let (row, column) = game.cursor_position();
+    State {
+        columns,
+        rows,
+        mines,
+        outcome: match game.outcome() {
+            Outcome::InProgress => "in-progress",
+            Outcome::Won => "won",
+            Outcome::Lost => "lost",
+        },
+        cursor: [row + 1, column + 1],
+        board: game.snapshot(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The JSON answers to `commands` played on a board with `layout`.
+    fn session(layout: &str, commands: &[&str]) -> Vec<serde_json::Value> {
+        let settings = Settings::default();
+        let mut game = Termsweeper::from_layout(layout, &settings).unwrap();
+        commands
+            .iter()
+            .map(|command| {
+                let words: Vec<&str> = command.split_whitespace().collect();
+                serde_json::to_value(respond(&mut game, &settings, &words)).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn plays_a_scripted_game() {
+        let responses = session(
+            "*...\n....\n...*\n",
+            &[
+                "move 2 3",
+                "flag",
+                "flag 2 3",
+                "reveal 1 4",
+                "reveal 1 4",
+                "reveal 9 1",
+                "jump",
+                "reveal 3 1",
+            ],
+        );
+        let ok: Vec<bool> = responses
+            .iter()
+            .map(|response| response["ok"].as_bool().unwrap())
+            .collect();
+        assert_eq!(ok, [true, true, true, true, false, false, false, true]);
+        assert_eq!(responses[0]["state"]["cursor"], serde_json::json!([2, 3]));
+        assert_eq!(responses[1]["state"]["board"][1][2], "F");
+        assert_eq!(responses[2]["state"]["board"][1][2], "?");
+        assert_eq!(
+            responses[3]["state"]["board"][0],
+            serde_json::json!(["?", "1", "0", "0"])
+        );
+        assert_eq!(responses[4]["error"], "Revealing (1,4) changed nothing");
+        assert_eq!(
+            responses[5]["error"],
+            "(9,1) is not on the board of 3 rows and 4 columns"
+        );
+        assert_eq!(responses[6]["error"], "Unknown command 'jump'");
+        assert!(responses[3].get("error").is_none());
+        assert_eq!(responses[7]["state"]["outcome"], "won");
+        assert_eq!(responses[7]["state"]["board"][2][0], "0");
+    }
+
+    #[test]
+    fn refuses_moves_once_the_game_is_over() {
+        let responses = session("*...\n....\n...*\n", &["reveal 1 1", "reveal 1 2"]);
+        assert_eq!(responses[0]["state"]["outcome"], "lost");
+        assert_eq!(responses[1]["ok"], false);
+        assert_eq!(
+            responses[1]["error"],
+            "The game is over, start a new one with new"
+        );
+    }
+}
@@ -0,0 +1,937 @@
+use crate::termsweeper::{
+    delete_by_renaming, CursorStyle, FloodFillPolicy, NumberGlyphs, StartPosition, SymbolSet,
+    Theme, MIN_SIDE,
+};
+use serde::{Deserialize, Serialize};
+use std::{fs, io, ops::RangeInclusive, path::PathBuf};
+
+/// Caps offered for the fields a single flood fill reveals, `0` doesn't limit it.
+const MAX_FLOOD_OPTIONS: [u16; 5] = [0, 10, 25, 50, 100];
+/// Fields left below which risky reveals ask for confirmation, `0` disables the guard.
+const REVEAL_GUARD_OPTIONS: [u16; 5] = [0, 5, 10, 20, 50];
+/// Lives per game offered, `1` being standard Minesweeper.
+const LIVES_OPTIONS: [u8; 4] = [1, 2, 3, 5];
+/// Peeks per game offered, `0` disables them.
+const PEEK_OPTIONS: [u8; 4] = [0, 1, 3, 5];
+/// Minimums offered for the fields left after the first reveal, `0` disables the check.
+const MIN_FIELDS_AFTER_FIRST_REVEAL_OPTIONS: [u16; 5] = [0, 10, 25, 50, 100];
+/// Minimums offered for the fields the first reveal opens, `0` disables the check.
+const MIN_OPENING_OPTIONS: [u16; 5] = [0, 10, 20, 40, 80];
+/// Field shapes offered on the settings screen as the square and jumbo settings: narrow,
+/// square and jumbo.
+const FIELD_SHAPES: [(bool, bool); 3] = [(false, false), (true, false), (false, true)];
+/// Standard boards offered as difficulties on the settings screen.
+const DIFFICULTIES: [(&str, BoardSize); 3] = [
+    (
+        "beginner",
+        BoardSize {
+            columns: 9,
+            rows: 9,
+            number_of_mines: 10,
+        },
+    ),
+    (
+        "intermediate",
+        BoardSize {
+            columns: 16,
+            rows: 16,
+            number_of_mines: 40,
+        },
+    ),
+    (
+        "expert",
+        BoardSize {
+            columns: 30,
+            rows: 16,
+            number_of_mines: 99,
+        },
+    ),
+];
+/// Largest board offered on the settings screen.
+const MAX_COLUMNS: u8 = 99;
+const MAX_ROWS: u8 = 99;
+/// Inactivity thresholds offered for the idle auto-pause, `0` disables it.
+const IDLE_PAUSE_OPTIONS: [u64; 5] = [0, 15, 30, 60, 120];
+/// Resting times offered for dwell reveals in milliseconds, `0` disables them.
+const DWELL_REVEAL_OPTIONS: [u64; 5] = [0, 1000, 1500, 2000, 3000];
+/// Intervals offered for polling while idle in milliseconds, `0` only wakes up for input.
+const IDLE_POLL_OPTIONS: [u64; 5] = [16, 100, 250, 1000, 0];
+/// Won games kept in the gallery offered, `0` doesn't save them.
+const GALLERY_SIZE_OPTIONS: [u16; 5] = [0, 10, 25, 50, 100];
+/// Seconds between autosaves offered, `0` turns autosaving off.
+const AUTOSAVE_OPTIONS: [u16; 5] = [0, 10, 30, 60, 300];
+/// Neighbourhood radii offered for numbers, see [`Settings::neighbor_radius`].
+const NEIGHBOR_RADIUS_OPTIONS: [u8; 2] = [1, 2];
+
+/// An option on the settings screen.
+struct Entry {
+    label: &'static str,
+    /// The current value as shown next to the label.
+    value: fn(&Settings) -> String,
+    /// Moves the option to its next value, or the previous one when not `forward`.
+    cycle: fn(&mut Settings, bool),
+}
+
+/// The options of the settings screen in the order they are listed.
+const ENTRIES: &[Entry] = &[
+    Entry {
+        label: "Difficulty",
+        value: |settings| {
+            if settings.auto_fit {
+                "fit the terminal"
+            } else {
+                settings.difficulty().map_or("custom", |(name, _)| name)
+            }
+            .to_string()
+        },
+        cycle: |settings, forward| settings.cycle_difficulty(forward),
+    },
+    Entry {
+        label: "Columns",
+        value: |settings| settings.board_value(settings.columns.to_string()),
+        cycle: |settings, forward| {
+            settings.columns = step(settings.columns, forward, MIN_SIDE..=MAX_COLUMNS);
+            settings.limit_mines();
+            settings.board_edited();
+        },
+    },
+    Entry {
+        label: "Rows",
+        value: |settings| settings.board_value(settings.rows.to_string()),
+        cycle: |settings, forward| {
+            settings.rows = step(settings.rows, forward, MIN_SIDE..=MAX_ROWS);
+            settings.limit_mines();
+            settings.board_edited();
+        },
+    },
+    Entry {
+        label: "Mines",
+        value: |settings| settings.board_value(settings.number_of_mines.to_string()),
+        cycle: |settings, forward| {
+            let cells = settings.columns as u16 * settings.rows as u16;
+            let max_mines = cells.saturating_sub(1).max(1);
+            settings.number_of_mines = step(settings.number_of_mines, forward, 1..=max_mines);
+            settings.board_edited();
+        },
+    },
+    Entry {
+        label: "Symbols",
+        value: |settings| settings.symbols.label().to_string(),
+        cycle: |settings, forward| {
+            settings.symbols = cycle(&SymbolSet::ALL, settings.symbols, forward)
+        },
+    },
+    Entry {
+        label: "Numbers",
+        value: |settings| settings.numbers.label().to_string(),
+        cycle: |settings, forward| settings.numbers = settings.numbers.cycle(forward),
+    },
+    Entry {
+        label: "Flood fill reveals",
+        value: |settings| {
+            if settings.flood_fill.reveal_borders {
+                "numbered borders"
+            } else {
+                "zeros only"
+            }
+            .to_string()
+        },
+        cycle: |settings, _| {
+            settings.flood_fill.reveal_borders = !settings.flood_fill.reveal_borders
+        },
+    },
+    Entry {
+        label: "Flood fill flags",
+        value: |settings| {
+            if settings.flood_fill.respect_flags {
+                "respect"
+            } else {
+                "reveal through"
+            }
+            .to_string()
+        },
+        cycle: |settings, _| settings.flood_fill.respect_flags = !settings.flood_fill.respect_flags,
+    },
+    Entry {
+        label: "Flood fill numbers",
+        value: |settings| {
+            if settings.flood_fill.through_numbers {
+                "continue past safe ones"
+            } else {
+                "stop"
+            }
+            .to_string()
+        },
+        cycle: |settings, _| {
+            settings.flood_fill.through_numbers = !settings.flood_fill.through_numbers
+        },
+    },
+    Entry {
+        label: "Flood fill animation",
+        value: |settings| on_off(settings.animate_flood_fill).to_string(),
+        cycle: |settings, _| settings.animate_flood_fill = !settings.animate_flood_fill,
+    },
+    Entry {
+        label: "Mines after a win",
+        value: |settings| {
+            if settings.flag_mines_on_win {
+                "flagged"
+            } else {
+                "revealed"
+            }
+            .to_string()
+        },
+        cycle: |settings, _| settings.flag_mines_on_win = !settings.flag_mines_on_win,
+    },
+    Entry {
+        label: "Start cursor",
+        value: |settings| settings.start_position.label().to_string(),
+        cycle: |settings, forward| {
+            settings.start_position = cycle(&StartPosition::ALL, settings.start_position, forward)
+        },
+    },
+    Entry {
+        label: "Idle auto-pause",
+        value: |settings| match settings.idle_pause_seconds {
+            0 => "off".to_string(),
+            seconds => format!("after {seconds}s"),
+        },
+        cycle: |settings, forward| {
+            settings.idle_pause_seconds =
+                cycle(&IDLE_PAUSE_OPTIONS, settings.idle_pause_seconds, forward)
+        },
+    },
+    Entry {
+        label: "Hide board while paused",
+        value: |settings| on_off(settings.idle_pause_obscures_board).to_string(),
+        cycle: |settings, _| {
+            settings.idle_pause_obscures_board = !settings.idle_pause_obscures_board
+        },
+    },
+    Entry {
+        label: "Reveal on resting cursor",
+        value: |settings| match settings.dwell_reveal_millis {
+            0 => "off".to_string(),
+            millis => format!("after {:.1}s", millis as f64 / 1000.0),
+        },
+        cycle: |settings, forward| {
+            settings.dwell_reveal_millis =
+                cycle(&DWELL_REVEAL_OPTIONS, settings.dwell_reveal_millis, forward)
+        },
+    },
+    Entry {
+        label: "Numbers count mines in",
+        value: |settings| {
+            let side = 2 * settings.neighbor_radius + 1;
+            format!("{side}×{side} square")
+        },
+        cycle: |settings, forward| {
+            settings.neighbor_radius =
+                cycle(&NEIGHBOR_RADIUS_OPTIONS, settings.neighbor_radius, forward)
+        },
+    },
+    Entry {
+        label: "Numbers count the field",
+        value: |settings| on_off(settings.self_inclusive_counts).to_string(),
+        cycle: |settings, _| settings.self_inclusive_counts = !settings.self_inclusive_counts,
+    },
+    Entry {
+        label: "Idle polling",
+        value: |settings| match settings.idle_poll_millis {
+            0 => "on input only".to_string(),
+            millis => format!("every {millis}ms"),
+        },
+        cycle: |settings, forward| {
+            settings.idle_poll_millis =
+                cycle(&IDLE_POLL_OPTIONS, settings.idle_poll_millis, forward)
+        },
+    },
+    Entry {
+        label: "Fields left after first reveal",
+        value: |settings| match settings.min_fields_after_first_reveal {
+            0 => "any".to_string(),
+            fields => format!("at least {fields}"),
+        },
+        cycle: |settings, forward| {
+            settings.min_fields_after_first_reveal = cycle(
+                &MIN_FIELDS_AFTER_FIRST_REVEAL_OPTIONS,
+                settings.min_fields_after_first_reveal,
+                forward,
+            )
+        },
+    },
+    Entry {
+        label: "First reveal opens",
+        value: |settings| match settings.min_opening {
+            0 => "any".to_string(),
+            fields => format!("at least {fields}"),
+        },
+        cycle: |settings, forward| {
+            settings.min_opening = cycle(&MIN_OPENING_OPTIONS, settings.min_opening, forward)
+        },
+    },
+    Entry {
+        label: "Peeks per game",
+        value: |settings| match settings.peeks {
+            0 => "off".to_string(),
+            peeks => peeks.to_string(),
+        },
+        cycle: |settings, forward| settings.peeks = cycle(&PEEK_OPTIONS, settings.peeks, forward),
+    },
+    Entry {
+        label: "Lives per game",
+        value: |settings| match settings.lives {
+            1 => "1 (standard)".to_string(),
+            lives => lives.to_string(),
+        },
+        cycle: |settings, forward| settings.lives = cycle(&LIVES_OPTIONS, settings.lives, forward),
+    },
+    Entry {
+        label: "Reveal on a number",
+        value: |settings| {
+            if settings.chord_on_reveal {
+                "opens neighbours"
+            } else {
+                "nothing"
+            }
+            .to_string()
+        },
+        cycle: |settings, _| settings.chord_on_reveal = !settings.chord_on_reveal,
+    },
+    Entry {
+        label: "Danger shading",
+        value: |settings| on_off(settings.danger_shading).to_string(),
+        cycle: |settings, _| settings.danger_shading = !settings.danger_shading,
+    },
+    Entry {
+        label: "Flood fill limit",
+        value: |settings| match settings.max_flood_per_click {
+            0 => "none".to_string(),
+            fields => format!("{fields} fields"),
+        },
+        cycle: |settings, forward| {
+            settings.max_flood_per_click =
+                cycle(&MAX_FLOOD_OPTIONS, settings.max_flood_per_click, forward)
+        },
+    },
+    Entry {
+        label: "Flagging completes numbers",
+        value: |settings| {
+            if settings.chord_on_flag {
+                "opens neighbours"
+            } else {
+                "nothing"
+            }
+            .to_string()
+        },
+        cycle: |settings, _| settings.chord_on_flag = !settings.chord_on_flag,
+    },
+    Entry {
+        label: "Cursor position",
+        value: |settings| on_off(settings.show_cursor_position).to_string(),
+        cycle: |settings, _| settings.show_cursor_position = !settings.show_cursor_position,
+    },
+    Entry {
+        label: "Checkerboard shading",
+        value: |settings| on_off(settings.checkerboard).to_string(),
+        cycle: |settings, _| settings.checkerboard = !settings.checkerboard,
+    },
+    Entry {
+        label: "Confirm risky reveals",
+        value: |settings| match settings.reveal_guard {
+            0 => "off".to_string(),
+            fields => format!("with {fields} fields left"),
+        },
+        cycle: |settings, forward| {
+            settings.reveal_guard = cycle(&REVEAL_GUARD_OPTIONS, settings.reveal_guard, forward)
+        },
+    },
+    Entry {
+        label: "Victory animation",
+        value: |settings| on_off(settings.celebrate_win).to_string(),
+        cycle: |settings, _| settings.celebrate_win = !settings.celebrate_win,
+    },
+    Entry {
+        label: "Satisfied numbers",
+        value: |settings| {
+            if settings.dim_satisfied_numbers {
+                "dimmed"
+            } else {
+                "normal"
+            }
+            .to_string()
+        },
+        cycle: |settings, _| settings.dim_satisfied_numbers = !settings.dim_satisfied_numbers,
+    },
+    Entry {
+        label: "Flags around numbers",
+        value: |settings| {
+            if settings.count_adjacent_flags {
+                "counted"
+            } else {
+                "not counted"
+            }
+            .to_string()
+        },
+        cycle: |settings, _| settings.count_adjacent_flags = !settings.count_adjacent_flags,
+    },
+    Entry {
+        label: "All mines flagged",
+        value: |settings| {
+            if settings.clear_when_flagged {
+                "reveal the rest"
+            } else {
+                "keep playing"
+            }
+            .to_string()
+        },
+        cycle: |settings, _| settings.clear_when_flagged = !settings.clear_when_flagged,
+    },
+    Entry {
+        label: "Field shape",
+        value: |settings| {
+            if settings.jumbo_fields {
+                "jumbo"
+            } else if settings.square_fields {
+                "square"
+            } else {
+                "narrow"
+            }
+            .to_string()
+        },
+        cycle: |settings, forward| {
+            let shape = (
+                settings.square_fields && !settings.jumbo_fields,
+                settings.jumbo_fields,
+            );
+            (settings.square_fields, settings.jumbo_fields) = cycle(&FIELD_SHAPES, shape, forward);
+        },
+    },
+    Entry {
+        label: "Cursor style",
+        value: |settings| settings.cursor_style.label().to_string(),
+        cycle: |settings, forward| {
+            settings.cursor_style = cycle(&CursorStyle::ALL, settings.cursor_style, forward)
+        },
+    },
+    Entry {
+        label: "Game frame",
+        value: |settings| {
+            if settings.fit_frame_to_board {
+                "fits the board"
+            } else {
+                "fixed size"
+            }
+            .to_string()
+        },
+        cycle: |settings, _| settings.fit_frame_to_board = !settings.fit_frame_to_board,
+    },
+    Entry {
+        label: "Loss analysis",
+        value: |settings| on_off(settings.analyze_losses).to_string(),
+        cycle: |settings, _| settings.analyze_losses = !settings.analyze_losses,
+    },
+    Entry {
+        label: "Mines after a loss",
+        value: |settings| {
+            if settings.blink_mines_on_loss {
+                "blinking"
+            } else {
+                "still"
+            }
+            .to_string()
+        },
+        cycle: |settings, _| settings.blink_mines_on_loss = !settings.blink_mines_on_loss,
+    },
+    Entry {
+        label: "Flags",
+        value: |settings| {
+            if settings.limit_flags {
+                "up to the mine count"
+            } else {
+                "unlimited"
+            }
+            .to_string()
+        },
+        cycle: |settings, _| settings.limit_flags = !settings.limit_flags,
+    },
+    Entry {
+        label: "Flag gauge",
+        value: |settings| on_off(settings.show_flag_gauge).to_string(),
+        cycle: |settings, _| settings.show_flag_gauge = !settings.show_flag_gauge,
+    },
+    Entry {
+        label: "Confirm first reveal",
+        value: |settings| on_off(settings.confirm_first_reveal).to_string(),
+        cycle: |settings, _| settings.confirm_first_reveal = !settings.confirm_first_reveal,
+    },
+    Entry {
+        label: "Gallery",
+        value: |settings| match settings.gallery_size {
+            0 => "off".to_string(),
+            size => format!("latest {size} wins"),
+        },
+        cycle: |settings, forward| {
+            settings.gallery_size = cycle(&GALLERY_SIZE_OPTIONS, settings.gallery_size, forward)
+        },
+    },
+    Entry {
+        label: "Key bar",
+        value: |settings| on_off(settings.show_key_bar).to_string(),
+        cycle: |settings, _| settings.show_key_bar = !settings.show_key_bar,
+    },
+    Entry {
+        label: "Enter key",
+        value: |settings| {
+            if settings.enter_reveals {
+                "reveals"
+            } else {
+                "marks"
+            }
+            .to_string()
+        },
+        cycle: |settings, _| settings.enter_reveals = !settings.enter_reveals,
+    },
+    Entry {
+        label: "Autosave",
+        value: |settings| match settings.autosave_seconds {
+            0 => "off".to_string(),
+            seconds => format!("every {seconds}s"),
+        },
+        cycle: |settings, forward| {
+            settings.autosave_seconds = cycle(&AUTOSAVE_OPTIONS, settings.autosave_seconds, forward)
+        },
+    },
+    Entry {
+        label: "Empty fields",
+        value: |settings| {
+            if settings.dot_empty_fields {
+                "dotted"
+            } else {
+                "blank"
+            }
+            .to_string()
+        },
+        cycle: |settings, _| settings.dot_empty_fields = !settings.dot_empty_fields,
+    },
+    Entry {
+        label: "Mine counter",
+        value: |settings| {
+            if settings.show_flag_count {
+                "flags placed"
+            } else {
+                "mines left"
+            }
+            .to_string()
+        },
+        cycle: |settings, _| settings.show_flag_count = !settings.show_flag_count,
+    },
+];
+
+/// Dimensions and mines of a board.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BoardSize {
+    columns: u8,
+    rows: u8,
+    number_of_mines: u16,
+}
+
+/// A choice of the difficulty option on the settings screen.
+#[derive(Clone, Copy, PartialEq)]
+enum Difficulty {
+    Standard(BoardSize),
+    Custom,
+    FitTerminal,
+}
+
+/// User options, persisted to `config.toml` in the platform's config directory. Options
+/// missing from the file keep their defaults.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub columns: u8,
+    pub rows: u8,
+    pub number_of_mines: u16,
+    /// The last board that isn't one of the difficulties, restored when the difficulty
+    /// cycles back to custom. `None` until a custom board was set.
+    pub custom_board: Option<BoardSize>,
+    /// Size new boards to fill the terminal instead of `columns` and `rows`, see
+    /// [`crate::termsweeper::Termsweeper::fitting_size`].
+    pub auto_fit: bool,
+    /// Only redraw after handled input (and while something is animating) instead of after
+    /// every event.
+    pub lazy_redraw: bool,
+    pub symbols: SymbolSet,
+    /// Glyphs for the numbers 1 to 8 as a list, e.g. `numbers = ["I", "II", "III", ...]`.
+    pub numbers: NumberGlyphs,
+    pub theme: Theme,
+    pub flood_fill: FloodFillPolicy,
+    /// Fields a single reveal opens at most, `0` doesn't limit it. Revealing a zero at the
+    /// edge of a capped flood fill continues it.
+    pub max_flood_per_click: u16,
+    /// Open flood filled areas ring by ring instead of at once, any key skips to the end.
+    pub animate_flood_fill: bool,
+    /// Revealing a number whose mines are all flagged reveals its other neighbours.
+    pub chord_on_reveal: bool,
+    /// Flagging a field chords the revealed numbers around it that it completes.
+    pub chord_on_flag: bool,
+    /// Flag the remaining mines of a won game instead of revealing them.
+    pub flag_mines_on_win: bool,
+    pub start_position: StartPosition,
+    /// Seconds without input after which a running game is paused, `0` disables it.
+    pub idle_pause_seconds: u64,
+    /// Hide the board while the game is paused, so it can't be studied with the timer
+    /// stopped.
+    pub idle_pause_obscures_board: bool,
+    /// Milliseconds the cursor has to rest on a field before it is revealed, `0` disables
+    /// it. An accessibility aid for players who can't easily press a separate action key.
+    pub dwell_reveal_millis: u64,
+    /// How many rows and columns away numbers count mines, `1` being standard Minesweeper.
+    /// Only applies to new games.
+    pub neighbor_radius: u8,
+    /// The self-inclusive variant: a field's number counts its own mine along with its
+    /// neighbourhood's. Numbers only show on safe fields, which add nothing, and a zero
+    /// still means no mine in its neighbourhood, so revealed numbers, chords and the fields
+    /// a zero opens stay the same. Mines count one more. Only applies to new games.
+    pub self_inclusive_counts: bool,
+    /// Milliseconds between checks for input while nothing is animating, `0` blocks until
+    /// input arrives. Timers, animations and dwell reveals still wake up when due, longer
+    /// intervals only save power.
+    pub idle_poll_millis: u64,
+    /// Peeks at covered fields per game, a casual assist. Games with a peek don't count
+    /// for the stats.
+    pub peeks: u8,
+    /// Place the mines again when the first reveal would leave fewer fields than this to
+    /// reveal, `0` disables the check. Keeps boards with few mines from being won with a
+    /// single click.
+    pub min_fields_after_first_reveal: u16,
+    /// Place the mines again when the first reveal would open fewer fields than this,
+    /// `0` disables the check. Saves clicking around for an opening on dense boards.
+    pub min_opening: u16,
+    /// Mines that can be revealed before the game is lost, each hit costs a life and
+    /// flags the mine. Games with more than one life don't count for the stats.
+    pub lives: u8,
+    /// Tint covered fields red by how many of their numbered neighbours' missing mines
+    /// they could hold. An assist, games with it don't count for the stats.
+    pub danger_shading: bool,
+    /// Show the 1-based `(row,column)` of the cursor in the header.
+    pub show_cursor_position: bool,
+    /// Shade every other field, for following rows and columns on large boards.
+    pub checkerboard: bool,
+    /// With at most this many safe fields left, revealing a field the deduction rules
+    /// can't prove safe asks for a second press. `0` disables it.
+    pub reveal_guard: u16,
+    /// Ask for a second press before the first reveal, which places the mines around it,
+    /// so a stray Space can't start the board in the wrong place.
+    pub confirm_first_reveal: bool,
+    /// Make Enter reveal like Space instead of marking like M, for players used to
+    /// confirming with it.
+    pub enter_reveals: bool,
+    /// Scatter confetti over the board for a moment after a win, any key skips it.
+    pub celebrate_win: bool,
+    /// Gray out revealed numbers once as many of their neighbours are flagged, leaving the
+    /// numbers that still need work stand out.
+    pub dim_satisfied_numbers: bool,
+    /// Show how many flags each revealed number has around it, as a subscript where the
+    /// field is wide enough and in red once there are too many.
+    pub count_adjacent_flags: bool,
+    /// Once the flags are on exactly the mines, reveal every other field and win.
+    pub clear_when_flagged: bool,
+    /// Draw fields twice as wide, so the board looks square despite tall terminal cells.
+    pub square_fields: bool,
+    /// Draw fields three times as wide and two lines tall, for boards watched on a stream:
+    /// numbers are bold with their dot pattern below, covered fields are textured. Takes
+    /// precedence over square fields.
+    pub jumbo_fields: bool,
+    /// Mark the cursor with brackets instead of a background, which leaves the colors of
+    /// the numbers under it readable.
+    pub cursor_style: CursorStyle,
+    /// Size the game screen to the board instead of the fixed frame of the other screens.
+    pub fit_frame_to_board: bool,
+    /// After a loss, point out a field the numbers proved safe before the fatal reveal, or
+    /// say that the loss was a forced guess.
+    pub analyze_losses: bool,
+    /// Make the revealed mines blink once a game is lost, to show at a glance where they
+    /// were.
+    pub blink_mines_on_loss: bool,
+    /// Refuse new flags once there are as many as mines, so the mine counter never goes
+    /// below zero.
+    pub limit_flags: bool,
+    /// Show a bar of the flags placed against the mines in the header, red once there are
+    /// more flags than mines.
+    pub show_flag_gauge: bool,
+    /// Won games kept in the gallery, the oldest are deleted beyond it. `0` doesn't save
+    /// won games.
+    pub gallery_size: u16,
+    /// Show the keys along the bottom of the game screen. Without them the status line
+    /// moves onto the bottom border, leaving its row to the board.
+    pub show_key_bar: bool,
+    /// Seconds between autosaves of the game in progress, so a crash loses at most that
+    /// much of it. `0` turns autosaving off.
+    pub autosave_seconds: u16,
+    /// Draw a faint dot on revealed fields without mines around them, so cleared areas
+    /// don't read as gaps in the board.
+    pub dot_empty_fields: bool,
+    /// Show flags placed against the total mines in the header instead of the mines left.
+    pub show_flag_count: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            columns: 45,
+            rows: 18,
+            number_of_mines: 75,
+            custom_board: None,
+            auto_fit: false,
+            lazy_redraw: true,
+            symbols: SymbolSet::Ascii,
+            numbers: NumberGlyphs::default(),
+            theme: Theme::default(),
+            flood_fill: FloodFillPolicy::default(),
+            max_flood_per_click: 0,
+            animate_flood_fill: false,
+            chord_on_reveal: false,
+            chord_on_flag: false,
+            flag_mines_on_win: false,
+            start_position: StartPosition::TopLeft,
+            idle_pause_seconds: 0,
+            idle_pause_obscures_board: false,
+            dwell_reveal_millis: 0,
+            neighbor_radius: 1,
+            self_inclusive_counts: false,
+            idle_poll_millis: 16,
+            peeks: 0,
+            min_fields_after_first_reveal: 0,
+            min_opening: 0,
+            lives: 1,
+            danger_shading: false,
+            show_cursor_position: false,
+            checkerboard: false,
+            reveal_guard: 0,
+            confirm_first_reveal: false,
+            enter_reveals: false,
+            celebrate_win: true,
+            dim_satisfied_numbers: false,
+            count_adjacent_flags: false,
+            clear_when_flagged: false,
+            square_fields: false,
+            jumbo_fields: false,
+            cursor_style: CursorStyle::Background,
+            fit_frame_to_board: false,
+            analyze_losses: true,
+            blink_mines_on_loss: false,
+            limit_flags: false,
+            show_flag_gauge: false,
+            gallery_size: 0,
+            show_key_bar: true,
+            autosave_seconds: 0,
+            dot_empty_fields: false,
+            show_flag_count: false,
+        }
+    }
+}
+
+impl Settings {
+    /// Loads the config file, falling back to the defaults when it is missing or invalid.
+    pub fn load() -> Settings {
+        Self::path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| toml::from_str::<Settings>(&content).ok())
+            .map(Settings::clamped)
+            .unwrap_or_default()
+    }
+
+    /// The settings with the board brought into the ranges the settings screen offers, as
+    /// an edited config file can hold anything that fits the types.
+    fn clamped(mut self) -> Settings {
+        self.columns = self.columns.clamp(MIN_SIDE, MAX_COLUMNS);
+        self.rows = self.rows.clamp(MIN_SIDE, MAX_ROWS);
+        self.neighbor_radius = self.neighbor_radius.clamp(
+            NEIGHBOR_RADIUS_OPTIONS[0],
+            NEIGHBOR_RADIUS_OPTIONS[NEIGHBOR_RADIUS_OPTIONS.len() - 1],
+        );
+        self.number_of_mines = self.number_of_mines.max(1);
+        self.limit_mines();
+        self
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let content = toml::to_string(self).map_err(io::Error::other)?;
+        fs::write(path, content)
+    }
+
+    /// Deletes the config file, see [`delete_by_renaming`].
+    pub fn delete_file() -> io::Result<()> {
+        Self::path().map_or(Ok(()), |path| delete_by_renaming(&path))
+    }
+
+    fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|directory| directory.join("termsweeper").join("config.toml"))
+    }
+
+    /// Label and current value of every option on the settings screen.
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        ENTRIES
+            .iter()
+            .map(|entry| (entry.label, (entry.value)(self)))
+            .collect()
+    }
+
+    /// Moves the option at `index` of [`Settings::entries`] to its next or previous value.
+    pub fn cycle(&mut self, index: usize, forward: bool) {
+        if let Some(entry) = ENTRIES.get(index) {
+            (entry.cycle)(self, forward);
+        }
+    }
+
+    /// Keeps a board set by hand on the settings screen: it no longer fits the terminal,
+    /// and it is remembered as the custom board unless it is one of the difficulties.
+    fn board_edited(&mut self) {
+        self.auto_fit = false;
+        if self.difficulty().is_none() {
+            self.custom_board = Some(self.board_size());
+        }
+    }
+
+    /// Value of a board dimension on the settings screen, which a fitted board only knows
+    /// once a game starts.
+    fn board_value(&self, value: String) -> String {
+        if self.auto_fit {
+            "to the terminal".to_string()
+        } else {
+            value
+        }
+    }
+
+    fn board_size(&self) -> BoardSize {
+        BoardSize {
+            columns: self.columns,
+            rows: self.rows,
+            number_of_mines: self.number_of_mines,
+        }
+    }
+
+    /// The difficulty the current board matches, `None` for a custom board.
+    fn difficulty(&self) -> Option<(&'static str, BoardSize)> {
+        DIFFICULTIES
+            .into_iter()
+            .find(|(_, board)| *board == self.board_size())
+    }
+
+    /// Switches to the next or previous difficulty, custom and then fitting the terminal
+    /// coming after the last one. A custom board is remembered before it is left and
+    /// restored when custom comes up again, the default board standing in until there is
+    /// one.
+    fn cycle_difficulty(&mut self, forward: bool) {
+        let current = match self.difficulty() {
+            _ if self.auto_fit => Difficulty::FitTerminal,
+            Some((_, board)) => Difficulty::Standard(board),
+            None => {
+                self.custom_board = Some(self.board_size());
+                Difficulty::Custom
+            }
+        };
+        let mut difficulties: Vec<Difficulty> = DIFFICULTIES
+            .into_iter()
+            .map(|(_, board)| Difficulty::Standard(board))
+            .collect();
+        difficulties.extend([Difficulty::Custom, Difficulty::FitTerminal]);
+        let next = cycle(&difficulties, current, forward);
+        self.auto_fit = next == Difficulty::FitTerminal;
+        let board = match next {
+            Difficulty::Standard(board) => board,
+            Difficulty::Custom => self
+                .custom_board
+                .unwrap_or_else(|| Settings::default().board_size()),
+            Difficulty::FitTerminal => return,
+        };
+        self.columns = board.columns;
+        self.rows = board.rows;
+        self.number_of_mines = board.number_of_mines;
+    }
+
+    /// Keeps at least one field free of mines after the board shrinks.
+    fn limit_mines(&mut self) {
+        let cells = self.columns as u16 * self.rows as u16;
+        self.number_of_mines = self.number_of_mines.min(cells.saturating_sub(1).max(1));
+    }
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+/// Counts up or down by one, stopping at the ends of `range` and of the type.
+fn step<T>(value: T, forward: bool, range: RangeInclusive<T>) -> T
+where
+    T: Copy + Into<u32> + TryFrom<u32>,
+{
+    let next = if forward {
+        value.into().saturating_add(1)
+    } else {
+        value.into().saturating_sub(1)
+    };
+    let next = next.clamp((*range.start()).into(), (*range.end()).into());
+    T::try_from(next).unwrap_or(value)
+}
+
+fn cycle<T: Copy + PartialEq>(values: &[T], current: T, forward: bool) -> T {
+    let index = values
+        .iter()
+        .position(|value| *value == current)
+        .unwrap_or(0);
+    let next = if forward {
+        (index + 1) % values.len()
+    } else {
+        (index + values.len() - 1) % values.len()
+    };
+    values[next]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamps_boards_from_an_edited_config() {
+        let settings: Settings =
+            toml::from_str("columns = 0\nrows = 255\nnumber_of_mines = 0\nneighbor_radius = 9")
+                .unwrap();
+        let settings = settings.clamped();
+        assert_eq!((settings.columns, settings.rows), (MIN_SIDE, MAX_ROWS));
+        assert_eq!(settings.number_of_mines, 1);
+        assert_eq!(settings.neighbor_radius, 2);
+    }
+
+    #[test]
+    fn every_entry_cycles_its_own_option() {
+        for (index, entry) in ENTRIES.iter().enumerate() {
+            let mut settings = Settings::default();
+            let before = settings.entries();
+            settings.cycle(index, true);
+            let after = settings.entries();
+            assert_ne!(after[index], before[index], "{}", entry.label);
+            settings.cycle(index, false);
+            assert_eq!(settings.entries(), before, "{}", entry.label);
+        }
+    }
+
+    #[test]
+    fn steps_stop_at_the_ends_of_the_type() {
+        assert_eq!(step(u8::MAX, true, 0..=u8::MAX), u8::MAX);
+        assert_eq!(step(0u16, false, 0..=10), 0);
+        assert_eq!(step(5u8, true, 2..=5), 5);
+        assert_eq!(step(3u8, false, 2..=5), 2);
+    }
+}
@@ -0,0 +1,472 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Largest group of connected covered cells that is solved by trying every arrangement.
+const MAX_ENUMERATED_CELLS: usize = 24;
+
+/// What the player can see of a single field.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Cell {
+    Hidden,
+    Flagged,
+    Revealed(u8),
+}
+
+/// Cells whose state follows from the revealed numbers without guessing.
+#[derive(Default)]
+pub struct Deductions {
+    pub safe: BTreeSet<(u8, u8)>,
+    pub mines: BTreeSet<(u8, u8)>,
+}
+
+impl Deductions {
+    /// Number of distinct forced moves, i.e. safe reveals plus sure flags.
+    pub fn count(&self) -> usize {
+        self.safe.len() + self.mines.len()
+    }
+}
+
+/// A single deducible cell and why it is deducible.
+pub struct Hint {
+    pub location: (u8, u8),
+    pub is_mine: bool,
+    pub reason: String,
+}
+
+/// The hidden neighbours of a revealed number and how many mines are still among them.
+struct Constraint {
+    source: (u8, u8),
+    number: u8,
+    cells: BTreeSet<(u8, u8)>,
+    mines: usize,
+}
+
+/// Collects every forced move using the single-number rules and the subset rule for pairs
+/// of numbers. Flags are trusted as placed. Numbers count the mines within `radius` rows
+/// and columns.
+pub fn forced_deductions(board: &[Vec<Cell>], radius: u8) -> Deductions {
+    let constraints = constraints(board, radius);
+    let mut deductions = Deductions::default();
+    for constraint in &constraints {
+        apply(&mut deductions, &constraint.cells, constraint.mines);
+    }
+    for a in &constraints {
+        for b in &constraints {
+            if a.cells.len() < b.cells.len() && a.cells.is_subset(&b.cells) && a.mines <= b.mines {
+                let difference = b.cells.difference(&a.cells).copied().collect();
+                apply(&mut deductions, &difference, b.mines - a.mines);
+            }
+        }
+    }
+    deductions
+}
+
+/// Finds one deducible cell, preferring the simplest rule that proves it. When only trying
+/// every arrangement of mines around the revealed numbers proves it, the explanation says
+/// so instead of walking through the arrangements.
+pub fn hint(board: &[Vec<Cell>], radius: u8) -> Option<Hint> {
+    find_hint(board, radius, |_| true)
+}
+
+/// Like [`hint`], but only for a cell that is provably safe to reveal.
+pub fn safe_hint(board: &[Vec<Cell>], radius: u8) -> Option<Hint> {
+    find_hint(board, radius, |hint| !hint.is_mine)
+}
+
+/// The first hint by the simplest rule that `accept` takes.
+fn find_hint(board: &[Vec<Cell>], radius: u8, accept: impl Fn(&Hint) -> bool) -> Option<Hint> {
+    let constraints = constraints(board, radius);
+    for constraint in &constraints {
+        let number = describe(constraint);
+        let Some(&location) = constraint.cells.first() else {
+            continue;
+        };
+        let hint = if constraint.mines == 0 {
+            // A revealed 0 can still have covered neighbours, e.g. left by a capped flood
+            // fill, and views ignoring flags count none.
+            let why = if constraint.number == 0 {
+                "touches no mines"
+            } else {
+                "already has all its mines flagged"
+            };
+            Hint {
+                location,
+                is_mine: false,
+                reason: format!("{} is safe because {number} {why}", coordinate(location)),
+            }
+        } else if constraint.mines == constraint.cells.len() {
+            Hint {
+                location,
+                is_mine: true,
+                reason: format!(
+                    "{} is a mine because {number} has exactly {} covered neighbours left for \
+                     its {} missing mines",
+                    coordinate(location),
+                    constraint.cells.len(),
+                    constraint.mines
+                ),
+            }
+        } else {
+            continue;
+        };
+        if accept(&hint) {
+            return Some(hint);
+        }
+    }
+    for a in &constraints {
+        for b in &constraints {
+            if a.cells.len() >= b.cells.len() || !a.cells.is_subset(&b.cells) || a.mines > b.mines {
+                continue;
+            }
+            let difference: BTreeSet<_> = b.cells.difference(&a.cells).copied().collect();
+            let Some(&location) = difference.first() else {
+                continue;
+            };
+            let hint = if b.mines == a.mines {
+                Hint {
+                    location,
+                    is_mine: false,
+                    reason: format!(
+                        "{} is safe because {} gets all its missing mines from the neighbours \
+                         it shares with {}",
+                        coordinate(location),
+                        describe(b),
+                        describe(a)
+                    ),
+                }
+            } else if b.mines - a.mines == difference.len() {
+                Hint {
+                    location,
+                    is_mine: true,
+                    reason: format!(
+                        "{} is a mine because {} can take at most {} of the {} mines {} \
+                         is missing",
+                        coordinate(location),
+                        describe(a),
+                        a.mines,
+                        b.mines,
+                        describe(b)
+                    ),
+                }
+            } else {
+                continue;
+            };
+            if accept(&hint) {
+                return Some(hint);
+            }
+        }
+    }
+    for group in groups(&constraints) {
+        let Some(arrangements) = enumerate(&group) else {
+            continue;
+        };
+        for (location, mines) in arrangements.mine_counts {
+            if mines == 0 || mines == arrangements.count {
+                let is_mine = mines != 0;
+                let hint = Hint {
+                    location,
+                    is_mine,
+                    reason: format!(
+                        "{} is {} in every arrangement of mines that fits the numbers around it",
+                        coordinate(location),
+                        if is_mine { "a mine" } else { "safe" }
+                    ),
+                };
+                if accept(&hint) {
+                    return Some(hint);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Chance of a mine on the covered `location`, the share of the arrangements of mines
+/// fitting the numbers around it that put one there. Only the group of numbers sharing
+/// covered cells with `location` is enumerated, each arrangement counting alike regardless
+/// of the mines left elsewhere. `None` when no number touches `location` or its group is too
+/// large to enumerate.
+pub fn mine_probability(board: &[Vec<Cell>], radius: u8, location: (u8, u8)) -> Option<f64> {
+    let constraints = constraints(board, radius);
+    let group = groups(&constraints).into_iter().find(|group| {
+        group
+            .iter()
+            .any(|constraint| constraint.cells.contains(&location))
+    })?;
+    let arrangements = enumerate(&group)?;
+    let mines = arrangements.mine_counts.get(&location)?;
+    Some(*mines as f64 / arrangements.count as f64)
+}
+
+/// 1-based `(row, column)`, as typed into the go-to command.
+fn coordinate(location: (u8, u8)) -> String {
+    format!("({},{})", location.0 + 1, location.1 + 1)
+}
+
+fn describe(constraint: &Constraint) -> String {
+    format!(
+        "the {} at {}",
+        constraint.number,
+        coordinate(constraint.source)
+    )
+}
+
+/// Splits the constraints into groups that share covered cells, directly or transitively.
+fn groups(constraints: &[Constraint]) -> Vec<Vec<&Constraint>> {
+    let mut grouped = vec![false; constraints.len()];
+    let mut groups = vec![];
+    for start in 0..constraints.len() {
+        if grouped[start] {
+            continue;
+        }
+        grouped[start] = true;
+        let mut group = vec![&constraints[start]];
+        let mut cells = constraints[start].cells.clone();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (i, constraint) in constraints.iter().enumerate() {
+                if !grouped[i] && !constraint.cells.is_disjoint(&cells) {
+                    grouped[i] = true;
+                    cells.extend(&constraint.cells);
+                    group.push(constraint);
+                    changed = true;
+                }
+            }
+        }
+        groups.push(group);
+    }
+    groups
+}
+
+/// The mine arrangements satisfying every constraint of a group.
+struct Arrangements {
+    count: u64,
+    /// How many of the arrangements put a mine on each cell.
+    mine_counts: BTreeMap<(u8, u8), u64>,
+}
+
+/// Tries every mine arrangement of a group, giving up on groups too large for that.
+fn enumerate(group: &[&Constraint]) -> Option<Arrangements> {
+    let cells: Vec<(u8, u8)> = group
+        .iter()
+        .flat_map(|constraint| constraint.cells.iter().copied())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    if cells.len() > MAX_ENUMERATED_CELLS {
+        return None;
+    }
+    let mut search = Search {
+        cells: &cells,
+        constraints: group,
+        assignment: vec![false; cells.len()],
+        arrangements: 0,
+        mine_counts: vec![0; cells.len()],
+    };
+    search.run(0);
+    if search.arrangements == 0 {
+        return None;
+    }
+    Some(Arrangements {
+        count: search.arrangements,
+        mine_counts: cells.iter().copied().zip(search.mine_counts).collect(),
+    })
+}
+
+struct Search<'a> {
+    cells: &'a [(u8, u8)],
+    constraints: &'a [&'a Constraint],
+    assignment: Vec<bool>,
+    arrangements: u64,
+    mine_counts: Vec<u64>,
+}
+
+impl Search<'_> {
+    fn run(&mut self, index: usize) {
+        if !self.consistent(index) {
+            return;
+        }
+        if index == self.cells.len() {
+            self.arrangements += 1;
+            for (count, mine) in self.mine_counts.iter_mut().zip(&self.assignment) {
+                *count += *mine as u64;
+            }
+            return;
+        }
+        for mine in [false, true] {
+            self.assignment[index] = mine;
+            self.run(index + 1);
+        }
+        self.assignment[index] = false;
+    }
+
+    /// Whether the first `assigned` cells can still be completed to a valid arrangement.
+    fn consistent(&self, assigned: usize) -> bool {
+        self.constraints.iter().all(|constraint| {
+            let mut mines = 0;
+            let mut open = 0;
+            for (i, cell) in self.cells.iter().enumerate() {
+                if constraint.cells.contains(cell) {
+                    if i >= assigned {
+                        open += 1;
+                    } else if self.assignment[i] {
+                        mines += 1;
+                    }
+                }
+            }
+            mines <= constraint.mines && mines + open >= constraint.mines
+        })
+    }
+}
+
+fn apply(deductions: &mut Deductions, cells: &BTreeSet<(u8, u8)>, mines: usize) {
+    if mines == 0 {
+        deductions.safe.extend(cells);
+    } else if mines == cells.len() {
+        deductions.mines.extend(cells);
+    }
+}
+
+fn constraints(board: &[Vec<Cell>], radius: u8) -> Vec<Constraint> {
+    let mut constraints = vec![];
+    for (row, cells) in board.iter().enumerate() {
+        for (column, cell) in cells.iter().enumerate() {
+            let Cell::Revealed(number) = *cell else {
+                continue;
+            };
+            let mut hidden = BTreeSet::new();
+            let mut flagged = 0;
+            for location in neighbours(board, (row as u8, column as u8), radius) {
+                match board[location.0 as usize][location.1 as usize] {
+                    Cell::Hidden => {
+                        hidden.insert(location);
+                    }
+                    Cell::Flagged => flagged += 1,
+                    Cell::Revealed(_) => (),
+                }
+            }
+            if !hidden.is_empty() && flagged <= number as usize {
+                constraints.push(Constraint {
+                    source: (row as u8, column as u8),
+                    number,
+                    cells: hidden,
+                    mines: number as usize - flagged,
+                });
+            }
+        }
+    }
+    constraints
+}
+
+fn neighbours(board: &[Vec<Cell>], location: (u8, u8), radius: u8) -> Vec<(u8, u8)> {
+    let radius = radius as i16;
+    let rows = board.len() as i16;
+    let columns = board.first().map_or(0, |row| row.len()) as i16;
+    let mut neighbours = vec![];
+    for row_offset in -radius..=radius {
+        for column_offset in -radius..=radius {
+            let row = location.0 as i16 + row_offset;
+            let column = location.1 as i16 + column_offset;
+            if (row_offset, column_offset) != (0, 0)
+                && (0..rows).contains(&row)
+                && (0..columns).contains(&column)
+            {
+                neighbours.push((row as u8, column as u8));
+            }
+        }
+    }
+    neighbours
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A board from rows of `?` for covered cells, `F` for flags and digits for revealed
+    /// numbers.
+    fn board(rows: &[&str]) -> Vec<Vec<Cell>> {
+        rows.iter()
+            .map(|row| {
+                row.chars()
+                    .map(|character| match character {
+                        '?' => Cell::Hidden,
+                        'F' => Cell::Flagged,
+                        digit => Cell::Revealed(digit.to_digit(10).unwrap() as u8),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn constraints_count_the_mines_missing_around_numbers() {
+        let constraints = constraints(&board(&["1F?", "2??", "???"]), 1);
+        let sources: Vec<_> = constraints
+            .iter()
+            .map(|constraint| (constraint.source, constraint.mines, constraint.cells.len()))
+            .collect();
+        // The 1 at (1,1) has its flag, the 2 at (2,1) still misses a mine among 3 cells.
+        assert_eq!(sources, [((0, 0), 0, 1), ((1, 0), 1, 3)]);
+    }
+
+    #[test]
+    fn constraints_skip_numbers_with_too_many_flags() {
+        assert!(constraints(&board(&["1F", "F?"]), 1).is_empty());
+    }
+
+    #[test]
+    fn forced_deductions_apply_the_single_number_rules() {
+        let deductions = forced_deductions(&board(&["1?", "??"]), 1);
+        assert!(deductions.safe.is_empty() && deductions.mines.is_empty());
+        let deductions = forced_deductions(&board(&["3?", "??"]), 1);
+        assert_eq!(deductions.mines.len(), 3);
+        let deductions = forced_deductions(&board(&["1F", "??"]), 1);
+        assert_eq!(deductions.safe, BTreeSet::from([(1, 0), (1, 1)]));
+    }
+
+    #[test]
+    fn forced_deductions_apply_the_subset_rule() {
+        // The outer 1s have their mine among the cells they share with the middle one, so
+        // its covered cells beyond them are safe.
+        let deductions = forced_deductions(&board(&["111", "???"]), 1);
+        assert_eq!(deductions.safe, BTreeSet::from([(1, 0), (1, 2)]));
+        assert!(deductions.mines.is_empty());
+    }
+
+    #[test]
+    fn forced_deductions_count_within_the_radius() {
+        let deductions = forced_deductions(&board(&["0??", "???", "???"]), 2);
+        assert_eq!(deductions.safe.len(), 8);
+    }
+
+    #[test]
+    fn hints_explain_zeros_and_flagged_numbers_apart() {
+        let zero = hint(&board(&["0?", "??"]), 1).unwrap();
+        assert!(!zero.is_mine);
+        assert_eq!(
+            zero.reason,
+            "(1,2) is safe because the 0 at (1,1) touches no mines"
+        );
+        let flagged = hint(&board(&["1F", "??"]), 1).unwrap();
+        assert_eq!(
+            flagged.reason,
+            "(2,1) is safe because the 1 at (1,1) already has all its mines flagged"
+        );
+    }
+
+    #[test]
+    fn hints_name_mines_and_safe_cells_from_the_subset_rule() {
+        let mine = hint(&board(&["3?", "??"]), 1).unwrap();
+        assert!(mine.is_mine);
+        assert_eq!(mine.location, (0, 1));
+        assert!(mine.reason.contains("exactly 3 covered neighbours"));
+        let safe = safe_hint(&board(&["111", "???"]), 1).unwrap();
+        assert_eq!(safe.location, (1, 2));
+        assert!(safe.reason.contains("gets all its missing mines"));
+    }
+
+    #[test]
+    fn no_hint_without_a_deduction() {
+        assert!(hint(&board(&["1?", "??"]), 1).is_none());
+    }
+}
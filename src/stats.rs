@@ -0,0 +1,190 @@
+use crate::termsweeper::delete_by_renaming;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, io, path::PathBuf, time::Duration};
+
+/// Schema version written to the stats file. Bump it together with a new arm in
+/// [`migrate`] whenever the persisted fields change.
+const VERSION: u32 = 2;
+
+/// Outcome of a finished game, as recorded in the stats.
+pub struct GameResult {
+    pub won: bool,
+    /// Dimensions and rules of the board, see [`board_key`].
+    pub board: String,
+    pub time: Duration,
+    /// Reveals and flags the player made, see [`Stats::best_clicks`].
+    pub clicks: u32,
+}
+
+/// Games played and best times, persisted to `stats.toml` in the platform's data
+/// directory.
+#[derive(Serialize, Deserialize)]
+pub struct Stats {
+    pub version: u32,
+    pub games_played: u32,
+    pub games_won: u32,
+    /// Fastest win in milliseconds per board, keyed by [`board_key`].
+    pub best_times: BTreeMap<String, u64>,
+    /// Fewest reveals and flags in a win per board, keyed like `best_times`.
+    pub best_clicks: BTreeMap<String, u32>,
+}
+
+impl Default for Stats {
+    fn default() -> Stats {
+        Stats {
+            version: VERSION,
+            games_played: 0,
+            games_won: 0,
+            best_times: BTreeMap::new(),
+            best_clicks: BTreeMap::new(),
+        }
+    }
+}
+
+impl Stats {
+    /// Loads the stats file, upgrading older versions. A missing file starts empty stats,
+    /// an unreadable or incompatible one is an error describing why it is ignored.
+    pub fn load() -> Result<Stats, String> {
+        let Some(path) = Self::path() else {
+            return Ok(Stats::default());
+        };
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Stats::default()),
+            Err(error) => return Err(format!("Can't read {}: {error}", path.display())),
+        };
+        let table: toml::Table = toml::from_str(&content)
+            .map_err(|_| format!("{} is not a valid stats file", path.display()))?;
+        let version = match table.get("version").and_then(toml::Value::as_integer) {
+            Some(version) => version as u32,
+            None => return Err(format!("{} has no version", path.display())),
+        };
+        migrate(table, version)?
+            .try_into()
+            .map_err(|_| format!("{} doesn't match version {version}", path.display()))
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let content = toml::to_string(self).map_err(io::Error::other)?;
+        fs::write(path, content)
+    }
+
+    /// Deletes the stats file, see [`delete_by_renaming`].
+    pub fn delete_file() -> io::Result<()> {
+        Self::path().map_or(Ok(()), |path| delete_by_renaming(&path))
+    }
+
+    fn path() -> Option<PathBuf> {
+        dirs::data_dir().map(|directory| directory.join("termsweeper").join("stats.toml"))
+    }
+
+    pub fn record(&mut self, result: GameResult) {
+        self.games_played += 1;
+        if result.won {
+            self.games_won += 1;
+            let millis = result.time.as_millis() as u64;
+            let best = self
+                .best_times
+                .entry(result.board.clone())
+                .or_insert(millis);
+            *best = millis.min(*best);
+            let best = self
+                .best_clicks
+                .entry(result.board)
+                .or_insert(result.clicks);
+            *best = result.clicks.min(*best);
+        }
+    }
+
+    pub fn best_time(&self, board: &str) -> Option<Duration> {
+        self.best_times
+            .get(board)
+            .map(|millis| Duration::from_millis(*millis))
+    }
+
+    pub fn best_clicks(&self, board: &str) -> Option<u32> {
+        self.best_clicks.get(board).copied()
+    }
+}
+
+/// Identifies boards whose times are comparable, e.g. `45x18-75` or `45x18-75-r2` for
+/// numbers counting a larger neighbourhood.
+pub fn board_key(columns: u8, rows: u8, number_of_mines: u16, neighbor_radius: u8) -> String {
+    match neighbor_radius {
+        1 => format!("{columns}x{rows}-{number_of_mines}"),
+        radius => format!("{columns}x{rows}-{number_of_mines}-r{radius}"),
+    }
+}
+
+/// Version 2 added the fewest clicks per board, unknown for the games before.
+fn upgrade_from_v1(mut table: toml::Table) -> toml::Table {
+    table.insert("version".to_string(), toml::Value::Integer(2));
+    table.insert(
+        "best_clicks".to_string(),
+        toml::Value::Table(toml::Table::new()),
+    );
+    table
+}
+
+/// Upgrades the raw contents of a stats file written with schema `version` to the current
+/// schema, one version at a time. Files from newer versions are refused rather than
+/// overwritten with less data.
+fn migrate(table: toml::Table, version: u32) -> Result<toml::Table, String> {
+    match version {
+        VERSION => Ok(table),
+        // A schema change adds an arm upgrading its predecessor here.
+        1 => migrate(upgrade_from_v1(table), 2),
+        version if version > VERSION => Err(format!(
+            "Stats were saved by a newer version (format {version}) and are not updated"
+        )),
+        version => Err(format!(
+            "Unknown stats format {version}, stats are not updated"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A stats file as version 1 wrote it, before the fewest clicks were recorded.
+    const V1_STATS: &str = r#"
+version = 1
+games_played = 7
+games_won = 3
+
+[best_times]
+"30x16-99" = 142500
+"9x9-10" = 11020
+"#;
+
+    #[test]
+    fn migrates_version_1() {
+        let table: toml::Table = toml::from_str(V1_STATS).unwrap();
+        let stats: Stats = migrate(table, 1).unwrap().try_into().unwrap();
+        assert_eq!(stats.version, VERSION);
+        assert_eq!(stats.games_played, 7);
+        assert_eq!(stats.games_won, 3);
+        assert_eq!(
+            stats.best_time("30x16-99"),
+            Some(Duration::from_millis(142500))
+        );
+        assert_eq!(
+            stats.best_time("9x9-10"),
+            Some(Duration::from_millis(11020))
+        );
+        assert!(stats.best_clicks.is_empty());
+    }
+
+    #[test]
+    fn refuses_newer_versions() {
+        let table: toml::Table = toml::from_str("version = 3").unwrap();
+        assert!(migrate(table, 3).is_err());
+    }
+}
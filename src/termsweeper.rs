@@ -1,15 +1,75 @@
-use crossterm::event::{KeyCode, KeyEvent};
-use rand::Rng;
+use crate::{
+    generator::{BoardGenerator, SeededGenerator},
+    settings::Settings,
+    solver, stats,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use ratatui::{
     buffer::Buffer,
     prelude::*,
     symbols::border,
     widgets::{block::*, *},
 };
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    fmt::Write,
+    fs, io,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::{Duration, Instant, SystemTime},
+};
+
+/// Debug-only keys for inspecting and short-circuiting games during development.
+const CHEATS_ENABLED: bool = cfg!(debug_assertions);
+const FLASH_DURATION: Duration = Duration::from_millis(1200);
+const FLASH_INTERVAL: Duration = Duration::from_millis(150);
+const SHAKE_DURATION: Duration = Duration::from_millis(400);
+const SHAKE_INTERVAL: Duration = Duration::from_millis(80);
+/// Delay between the rings of fields a flood fill animation opens.
+const FLOOD_WAVE_INTERVAL: Duration = Duration::from_millis(30);
+/// How long confetti covers the board after a win.
+const CELEBRATION_DURATION: Duration = Duration::from_millis(1000);
+/// Delay between the confetti frames of the victory animation.
+const CELEBRATION_INTERVAL: Duration = Duration::from_millis(80);
+/// Glyphs and colors of the victory confetti, all plain ASCII to fit any symbol set.
+const CONFETTI_GLYPHS: [char; 5] = ['*', '+', 'o', '.', '\''];
+const CONFETTI_COLORS: [Color; 6] = [
+    Color::Yellow,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::LightGreen,
+    Color::LightRed,
+    Color::LightBlue,
+];
+/// Mine placements tried to meet the minimum of fields left after the first reveal.
+const GENERATION_ATTEMPTS: usize = 100;
+/// How long a peek shows the content of a field.
+const PEEK_DURATION: Duration = Duration::from_millis(1500);
+/// How long confirmations like a saved game stay in the status line.
+const STATUS_DURATION: Duration = Duration::from_secs(3);
+/// Moves kept in the history, older ones are dropped.
+const MAX_HISTORY: usize = 500;
+/// Share of the fields that are mines on boards sized to the terminal, close to expert.
+const AUTO_FIT_DENSITY: f64 = 0.2;
+/// Fewest rows and columns of a board, the border drawing assumes at least two of each.
+pub const MIN_SIDE: u8 = 2;
+/// Width of the history panel including its border.
+const HISTORY_WIDTH: u16 = 26;
+/// Characters of the flag gauge in the header, see [`Settings::show_flag_gauge`].
+const FLAG_GAUGE_WIDTH: usize = 10;
+/// Background of every other field with checkerboard shading, a dark gray of the 256
+/// color palette that stays subtle next to the theme colors.
+const CHECKERBOARD_SHADE: Color = Color::Indexed(236);
 
 pub enum AppState {
     TitleScreen,
     GameScreen,
+    Settings,
+    Tutorial,
+    Gallery,
 }
 
 enum GameState {
@@ -18,11 +78,508 @@ enum GameState {
     Won,
 }
 
+/// What a reveal or flag came to, see [`Termsweeper::click`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum ClickResult {
+    /// Fields were revealed or a flag changed, which counts as a click.
+    Acted,
+    /// The board stayed as it was, but the key did something, like asking for a second
+    /// press or telling why it was refused.
+    Refused,
+    /// Nothing to do, the key is left unhandled.
+    Ignored,
+}
+
+impl ClickResult {
+    fn handled(self) -> bool {
+        self != ClickResult::Ignored
+    }
+}
+
+impl From<bool> for ClickResult {
+    fn from(acted: bool) -> ClickResult {
+        if acted {
+            ClickResult::Acted
+        } else {
+            ClickResult::Ignored
+        }
+    }
+}
+
+/// How a game stands, as seen from outside, see [`Termsweeper::outcome`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Outcome {
+    InProgress,
+    Won,
+    Lost,
+}
+
+/// Where the cursor is placed when a new game starts.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StartPosition {
+    TopLeft,
+    Center,
+    /// Where the cursor was when the previous game was left.
+    LastUsed,
+}
+
+impl StartPosition {
+    pub const ALL: [StartPosition; 3] = [
+        StartPosition::TopLeft,
+        StartPosition::Center,
+        StartPosition::LastUsed,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StartPosition::TopLeft => "top-left",
+            StartPosition::Center => "center",
+            StartPosition::LastUsed => "last used",
+        }
+    }
+}
+
+/// How the field under the cursor is marked.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CursorStyle {
+    /// Fill the field with the cursor color.
+    Background,
+    /// Draw brackets on the separators left and right of the field, keeping its colors.
+    Brackets,
+}
+
+impl CursorStyle {
+    pub const ALL: [CursorStyle; 2] = [CursorStyle::Background, CursorStyle::Brackets];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CursorStyle::Background => "background",
+            CursorStyle::Brackets => "brackets",
+        }
+    }
+}
+
+/// The last rendered board, so a frame only has to re-render the fields that changed.
+///
+/// Rendering the default 45×18 board from scratch takes about 2.9ms in a release build,
+/// while copying the cached board with only the cursor re-rendered takes about 0.06ms,
+/// which matters while the timer and animations redraw every tick. A change of the board
+/// area (e.g. a resize) or of the render options falls back to a full render.
+#[derive(Default)]
+struct BoardCache {
+    frame: Option<CachedFrame>,
+    /// Fields mutated since the last frame.
+    dirty: BTreeSet<(u8, u8)>,
+}
+
+struct CachedFrame {
+    area: Rect,
+    options: RenderOptions,
+    buffer: Buffer,
+    /// Area and borders of every field, indexed like the board.
+    fields: Vec<Vec<(Rect, Borders)>>,
+    cursor: (u8, u8),
+    /// Start and wave of the flood fill animation when the frame was rendered.
+    flood_wave: Option<(Instant, u16)>,
+}
+
+/// The seed a board was scattered from, and the first reveal it kept the mines away from.
+#[derive(Clone, Copy)]
+struct BoardSeed {
+    seed: u64,
+    first_reveal: (u8, u8),
+}
+
+/// What the numbers showed right before a lost game's fatal reveal.
+struct LossAnalysis {
+    /// A field the player could have revealed safely instead.
+    safe: Option<(u8, u8)>,
+    explanation: String,
+}
+
+/// Fields opened by a flood fill, shown ring by ring around the revealed field. The fields
+/// are already revealed, the animation only holds back drawing them.
+struct FloodAnimation {
+    started: Instant,
+    /// Ring of every opened field, its distance to the revealed field.
+    waves: BTreeMap<(u8, u8), u16>,
+    last_wave: u16,
+}
+
+impl FloodAnimation {
+    /// The outermost ring shown so far.
+    fn wave(&self) -> u16 {
+        (self.started.elapsed().as_millis() / FLOOD_WAVE_INTERVAL.as_millis()).min(u16::MAX as u128)
+            as u16
+    }
+
+    fn is_running(&self) -> bool {
+        self.wave() < self.last_wave
+    }
+}
+
+/// Measures playing time, excluding the time spent paused.
+#[derive(Default)]
+struct Stopwatch {
+    running_since: Option<Instant>,
+    elapsed: Duration,
+}
+
+impl Stopwatch {
+    fn start(&mut self) {
+        if self.running_since.is_none() {
+            self.running_since = Some(Instant::now());
+        }
+    }
+
+    fn stop(&mut self) {
+        if let Some(running_since) = self.running_since.take() {
+            self.elapsed += running_since.elapsed();
+        }
+    }
+
+    fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.elapsed
+            + self
+                .running_since
+                .map_or(Duration::ZERO, |running_since| running_since.elapsed())
+    }
+}
+
+/// What the player did in a [`Move`].
+#[derive(Clone, Copy)]
+enum Action {
+    Reveal,
+    Flag,
+    Unflag,
+}
+
+impl Action {
+    fn label(self) -> &'static str {
+        match self {
+            Action::Reveal => "reveal",
+            Action::Flag => "flag",
+            Action::Unflag => "unflag",
+        }
+    }
+}
+
+/// An entry of the move history.
+struct Move {
+    at: Instant,
+    action: Action,
+    location: (u8, u8),
+}
+
+/// Glyphs used for the non-numeric field states.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SymbolSet {
+    Ascii,
+    /// Double-width emoji, only used when the wider fields still fit the terminal.
+    Emoji,
+}
+
+impl SymbolSet {
+    fn unrevealed(self) -> &'static str {
+        "?" // ⣿ ⠶
+    }
+
+    fn marked(self) -> &'static str {
+        match self {
+            SymbolSet::Ascii => "X",
+            SymbolSet::Emoji => "🚩",
+        }
+    }
+
+    /// A covered field the player is unsure about, see [`Termsweeper::toggle_maybe`].
+    fn maybe(self) -> &'static str {
+        match self {
+            SymbolSet::Ascii => "~",
+            SymbolSet::Emoji => "❔",
+        }
+    }
+
+    fn mine(self) -> &'static str {
+        match self {
+            SymbolSet::Ascii => "*",
+            SymbolSet::Emoji => "💣",
+        }
+    }
+
+    /// A revealed field without mines around it, see [`Settings::dot_empty_fields`].
+    fn empty(self) -> &'static str {
+        match self {
+            SymbolSet::Ascii => ".",
+            SymbolSet::Emoji => "·",
+        }
+    }
+
+    pub const ALL: [SymbolSet; 2] = [SymbolSet::Ascii, SymbolSet::Emoji];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SymbolSet::Ascii => "ASCII",
+            SymbolSet::Emoji => "emoji",
+        }
+    }
+
+    /// Width of the widest symbol in terminal columns.
+    fn width(self) -> u16 {
+        [self.unrevealed(), self.marked(), self.maybe(), self.mine()]
+            .iter()
+            .map(|symbol| Span::raw(*symbol).width() as u16)
+            .max()
+            .unwrap_or(1)
+    }
+}
+
+/// Glyphs shown for the numbers 1 to 8, e.g. Roman numerals or dot patterns. Numbers
+/// without a usable glyph, like those above 8 in the 5×5 variant, are shown as digits.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct NumberGlyphs(Vec<String>);
+
+impl NumberGlyphs {
+    /// Widest glyph in terminal columns, wider glyphs fall back to digits so a typo in the
+    /// config can't stretch the board off the screen.
+    const MAX_WIDTH: u16 = 4;
+
+    const PRESETS: [(&'static str, [&'static str; 8]); 3] = [
+        ("digits", ["1", "2", "3", "4", "5", "6", "7", "8"]),
+        (
+            "Roman numerals",
+            ["I", "II", "III", "IV", "V", "VI", "VII", "VIII"],
+        ),
+        ("dots", ["⠁", "⠃", "⠇", "⡇", "⡏", "⡟", "⡿", "⣿"]),
+    ];
+
+    fn preset(index: usize) -> NumberGlyphs {
+        NumberGlyphs(
+            Self::PRESETS[index]
+                .1
+                .iter()
+                .map(|glyph| glyph.to_string())
+                .collect(),
+        )
+    }
+
+    fn preset_index(&self) -> Option<usize> {
+        Self::PRESETS
+            .iter()
+            .position(|(_, glyphs)| self.0.iter().eq(glyphs.iter()))
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self.preset_index() {
+            Some(index) => Self::PRESETS[index].0,
+            None => "custom",
+        }
+    }
+
+    /// The next or previous preset, custom glyphs continue with the first one.
+    pub fn cycle(&self, forward: bool) -> NumberGlyphs {
+        let presets = Self::PRESETS.len();
+        Self::preset(match self.preset_index() {
+            Some(index) if forward => (index + 1) % presets,
+            Some(index) => (index + presets - 1) % presets,
+            None => 0,
+        })
+    }
+
+    /// Dot pattern of `number` from 1 to 8, shown under the numbers of jumbo fields.
+    fn dots(number: u8) -> &'static str {
+        Self::PRESETS[2].1[number as usize - 1]
+    }
+
+    fn glyph(&self, number: u8) -> Option<&str> {
+        let glyph = self.0.get(number.checked_sub(1)? as usize)?;
+        let width = Span::raw(glyph.as_str()).width() as u16;
+        (1..=Self::MAX_WIDTH)
+            .contains(&width)
+            .then_some(glyph.as_str())
+    }
+
+    /// Width of the widest usable glyph in terminal columns.
+    fn width(&self) -> u16 {
+        (1..=8)
+            .filter_map(|number| self.glyph(number))
+            .map(|glyph| Span::raw(glyph).width() as u16)
+            .max()
+            .unwrap_or(1)
+    }
+}
+
+impl Default for NumberGlyphs {
+    fn default() -> NumberGlyphs {
+        Self::preset(0)
+    }
+}
+
+/// Controls which fields `reveal` opens automatically around a field without adjacent mines.
+///
+/// The default reveals the numbered border of an opened area and ignores flags, which is
+/// how the flood fill has always behaved.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FloodFillPolicy {
+    /// Also reveal numbered fields bordering the area instead of only fields without
+    /// adjacent mines.
+    pub reveal_borders: bool,
+    /// Leave flagged fields covered instead of revealing through them.
+    pub respect_flags: bool,
+    /// Keep opening past the numbered border while its numbers prove covered fields next
+    /// to the area safe. Only the numbers count, flags are ignored for this.
+    pub through_numbers: bool,
+}
+
+impl Default for FloodFillPolicy {
+    fn default() -> FloodFillPolicy {
+        FloodFillPolicy {
+            reveal_borders: true,
+            respect_flags: false,
+            through_numbers: false,
+        }
+    }
+}
+
+/// Colors of the field contents.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    /// Colors of the numbers 1 to 8.
+    pub numbers: [Color; 8],
+    pub mine: Color,
+    pub marked: Color,
+    pub maybe: Color,
+    pub unrevealed: Color,
+    pub cursor: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme {
+            numbers: [
+                Color::LightBlue,
+                Color::LightGreen,
+                Color::LightYellow,
+                Color::LightRed,
+                Color::Red,
+                Color::LightMagenta,
+                Color::Magenta,
+                Color::Magenta,
+            ],
+            mine: Color::Red,
+            marked: Color::Red,
+            maybe: Color::LightCyan,
+            unrevealed: Color::DarkGray,
+            cursor: Color::Green,
+        }
+    }
+}
+
+/// Board-wide options shared by every field rendered in a frame.
+#[derive(Clone, PartialEq)]
+struct RenderOptions {
+    symbols: SymbolSet,
+    numbers: NumberGlyphs,
+    /// Characters needed for the largest number the neighbourhood allows.
+    number_width: u16,
+    theme: Theme,
+    /// Shade every other field to make rows and columns easier to follow.
+    checkerboard: bool,
+    /// Debug view showing the contents of unrevealed fields.
+    x_ray: bool,
+    /// Colors flags by whether they are on a mine.
+    flag_assist: bool,
+    /// Gray out numbers with as many flags around them.
+    dim_satisfied: bool,
+    /// Show the flags around revealed numbers.
+    count_flags: bool,
+    /// Double the width of the field contents to make up for cells twice as tall as wide.
+    square_fields: bool,
+    /// Fields three times as wide and two lines tall, see [`Settings::jumbo_fields`].
+    jumbo_fields: bool,
+    /// Blink the revealed mines of a lost game.
+    blink_mines: bool,
+    /// See [`Settings::dot_empty_fields`].
+    dot_empty_fields: bool,
+}
+
+impl RenderOptions {
+    /// Width of a field including its right separator.
+    fn field_size(&self) -> u16 {
+        let content = self
+            .symbols
+            .width()
+            .max(self.numbers.width())
+            .max(self.number_width);
+        if self.jumbo_fields {
+            content * 3 + 1
+        } else if self.square_fields {
+            content * 2 + 1
+        } else {
+            content + 1
+        }
+    }
+
+    /// Height of a field including its bottom separator.
+    fn row_size(&self) -> u16 {
+        if self.jumbo_fields {
+            3
+        } else {
+            2
+        }
+    }
+}
+
+/// Where and how a field is drawn in a frame, besides the board-wide options.
+#[derive(Clone, Copy)]
+struct FieldFrame {
+    /// Board coordinates of the field.
+    location: (u8, u8),
+    area: Rect,
+    borders: Borders,
+    /// Background of the cursor or a selected field.
+    highlight: Option<Color>,
+    peeked: bool,
+    /// A revealed number with as many flags around it, grayed out.
+    satisfied: bool,
+    /// Flags around the field, see [`RenderOptions::count_flags`].
+    adjacent_flags: u8,
+}
+
+/// Draws single fields, to try other looks without changing the board rendering.
+trait CellRenderer {
+    fn render(&self, field: &Field, frame: &FieldFrame, buf: &mut Buffer, options: &RenderOptions);
+}
+
+/// The standard look, see [`Field::render`].
+struct DefaultCellRenderer;
+
+impl CellRenderer for DefaultCellRenderer {
+    fn render(&self, field: &Field, frame: &FieldFrame, buf: &mut Buffer, options: &RenderOptions) {
+        field.render(frame, buf, options);
+    }
+}
+
 #[derive(Clone)]
 pub struct Field {
     revealed: bool,
     marked: bool,
+    /// Noted as uncertain. Unlike a flag it neither counts as a mine nor blocks a reveal.
+    maybe: bool,
     is_mine: bool,
+    /// Mine revealed at the cost of a life, shown flagged from then on.
+    exploded: bool,
+    /// Mines within the neighbourhood radius, the field itself included with
+    /// [`Settings::self_inclusive_counts`].
     adjacent_mines: u8,
 }
 
@@ -31,15 +588,25 @@ impl Field {
         Field {
             revealed: false,
             marked: false,
+            maybe: false,
             is_mine: false,
+            exploded: false,
             adjacent_mines: 0,
         }
     }
 
-    fn render(&self, area: Rect, buf: &mut Buffer, borders: Borders, cursor: bool) {
-        const SYMBOL_DEFAULT: &str = "?"; // ⣿ ⠶
-        const SYMBOL_MARKED: &str = "X";
-        const SYMBOL_MINE: &str = "*";
+    fn render(&self, frame: &FieldFrame, buf: &mut Buffer, options: &RenderOptions) {
+        let FieldFrame {
+            location,
+            area,
+            borders,
+            highlight,
+            peeked,
+            satisfied,
+            adjacent_flags,
+        } = *frame;
+        let symbols = options.symbols;
+        let theme = &options.theme;
         let border_set = symbols::border::Set {
             bottom_right: symbols::line::CROSS,
             ..symbols::border::PLAIN
@@ -49,41 +616,133 @@ impl Field {
             .borders(borders)
             .border_style(Style::new().dark_gray());
         let (text, mut style) = if self.revealed {
-            if self.is_mine {
-                (SYMBOL_MINE, Style::default().fg(Color::Red))
-            } else {
-                match self.adjacent_mines {
-                    0 => (" ", Style::default()),
-                    1 => ("1", Style::default().fg(Color::LightBlue)),
-                    2 => ("2", Style::default().fg(Color::LightGreen)),
-                    3 => ("3", Style::default().fg(Color::LightYellow)),
-                    4 => ("4", Style::default().fg(Color::LightRed)),
-                    5 => ("5", Style::default().fg(Color::Red)),
-                    6 => ("6", Style::default().fg(Color::LightMagenta)),
-                    7 => ("7", Style::default().fg(Color::Magenta)),
-                    8 => ("8", Style::default().fg(Color::Magenta)),
-                    _ => (SYMBOL_DEFAULT, Style::default()),
-                }
-            }
+            self.revealed_symbol(options)
+        } else if peeked {
+            let (text, style) = self.revealed_symbol(options);
+            (text, style.bg(Color::Blue))
+        } else if self.exploded {
+            (symbols.mine(), Style::default().fg(theme.mine))
         } else if self.marked {
-            (SYMBOL_MARKED, Style::default().fg(Color::Red))
+            (symbols.marked(), Style::default().fg(theme.marked))
+        } else if self.maybe {
+            (symbols.maybe(), Style::default().fg(theme.maybe))
+        } else if options.x_ray {
+            let (text, style) = self.revealed_symbol(options);
+            (text, style.add_modifier(Modifier::DIM))
         } else {
-            (SYMBOL_DEFAULT, Style::default().fg(Color::DarkGray))
+            (symbols.unrevealed(), Style::default().fg(theme.unrevealed))
         };
-        if cursor {
-            style = style.bg(Color::Green);
+        if satisfied && self.revealed {
+            style = style.fg(Color::DarkGray);
+        }
+        let counted = options.count_flags
+            && self.revealed
+            && !self.is_mine
+            && self.adjacent_mines > 0
+            && adjacent_flags > 0;
+        if counted && adjacent_flags > self.adjacent_mines {
+            style = style.fg(Color::LightRed);
+        } else if counted && adjacent_flags == self.adjacent_mines && !satisfied {
+            style = style.fg(Color::Green);
+        }
+        if options.checkerboard && (location.0 as u16 + location.1 as u16) % 2 == 1 {
+            style = style.bg(CHECKERBOARD_SHADE);
+        }
+        if options.x_ray && !self.revealed && self.is_mine {
+            style = style.bg(Color::Red);
+        }
+        if options.flag_assist && self.marked && !self.revealed {
+            style = style.bg(if self.is_mine {
+                Color::LightGreen
+            } else {
+                Color::LightRed
+            });
+        }
+        if let Some(highlight_color) = highlight.filter(|_| !peeked) {
+            style = style.bg(highlight_color);
         }
-        if self.revealed &&  self.marked {
+        if self.revealed && self.marked {
             if self.is_mine {
                 style = style.bg(Color::LightGreen)
-            }
-            else {
+            } else {
                 style = style.bg(Color::LightBlue)
             }
         }
-        Paragraph::new(Span::styled(text, style))
-            .block(border)
-            .render(area, buf);
+        if self.exploded {
+            style = style.bg(Color::Red);
+        }
+        if options.blink_mines && self.revealed && self.is_mine {
+            style = style.add_modifier(Modifier::SLOW_BLINK);
+        }
+        let inner_area = border.inner(area);
+        border.render(area, buf);
+        let number_shown = !self.is_mine
+            && (1..=8).contains(&self.adjacent_mines)
+            && text == self.revealed_symbol(options).0;
+        let covered = text == symbols.unrevealed();
+        let mut text = text.to_string();
+        if counted && Line::from(text.as_str()).width() < inner_area.width as usize {
+            text.push(subscript(adjacent_flags));
+        }
+        // Jumbo fields are two lines: covered ones textured, numbers bold above their dots.
+        let lines = if options.jumbo_fields && covered {
+            let texture = "░".repeat(inner_area.width as usize);
+            vec![
+                Line::from(Span::styled(texture.clone(), style)),
+                Line::from(Span::styled(texture, style)),
+            ]
+        } else if options.jumbo_fields && number_shown {
+            vec![
+                Line::from(Span::styled(text, style.bold())),
+                Line::from(Span::styled(NumberGlyphs::dots(self.adjacent_mines), style)),
+            ]
+        } else if options.jumbo_fields {
+            vec![Line::from(Span::styled(text, style)), Line::default()]
+        } else {
+            vec![Line::from(Span::styled(text, style))]
+        };
+        let text_area = Rect {
+            y: inner_area.y + inner_area.height.saturating_sub(lines.len() as u16) / 2,
+            height: inner_area.height.min(lines.len() as u16),
+            ..inner_area
+        };
+        // The background spans the whole width of wider fields, not only the glyph.
+        let background = style
+            .bg
+            .map_or(Style::default(), |color| Style::default().bg(color));
+        Paragraph::new(lines)
+            .centered()
+            .style(background)
+            .render(text_area, buf);
+    }
+
+    fn revealed_symbol<'a>(&self, options: &'a RenderOptions) -> (&'a str, Style) {
+        const NUMBERS: [&str; 24] = [
+            "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16",
+            "17", "18", "19", "20", "21", "22", "23", "24",
+        ];
+        if self.is_mine {
+            (
+                options.symbols.mine(),
+                Style::default().fg(options.theme.mine),
+            )
+        } else {
+            match self.adjacent_mines {
+                0 if options.dot_empty_fields => (
+                    options.symbols.empty(),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                0 => (" ", Style::default()),
+                number @ 1..=24 => (
+                    options
+                        .numbers
+                        .glyph(number)
+                        .unwrap_or(NUMBERS[number as usize - 1]),
+                    Style::default().fg(options.theme.numbers[number.min(8) as usize - 1]),
+                ),
+                _ => (options.symbols.unrevealed(), Style::default()),
+            }
+        }
     }
 }
 
@@ -98,195 +757,1170 @@ impl Row {
             fields: vec![Field::new(); entries.into()],
         }
     }
-
-    fn render(&self, area: Rect, buf: &mut Buffer, borders: Borders, cursor_location: Option<u8>) {
-        const FIELD_SIZE: u16 = 2;
-        let fields = self.fields.len();
-        let mut constraints = vec![Constraint::Min(0)];
-        constraints.append(&mut Constraint::from_maxes(vec![FIELD_SIZE; fields - 1]));
-        constraints.push(Constraint::Max(FIELD_SIZE - 1));
-        constraints.push(Constraint::Min(0));
-        let layout = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(constraints)
-            .split(area);
-        let mut i = 1;
-        for field in &self.fields {
-            let field_border = if i == self.fields.len() {
-                Borders::NONE
-            } else {
-                Borders::RIGHT | borders
-            };
-            let cursor = match cursor_location {
-                Some(field_location) if i - 1 == field_location.into() => true,
-                _ => false,
-            };
-            field.render(layout[i], buf, field_border | borders, cursor);
-            i += 1;
-        }
-    }
 }
 
 pub struct Termsweeper {
     columns: u8,
     rows: u8,
     number_of_mines: u16,
+    /// Safe fields still covered, the game is won at zero. A flag on a safe field only
+    /// holds the win back until it is removed, flags can always be taken off covered fields.
     fields_left_to_reveal: u16,
     board: Vec<Row>,
     cursor: (u8, u8),
     initialized: bool,
+    /// Whether the mines were placed around the first reveal. Imported boards come with
+    /// theirs in place and aren't, without making the boards after them hardcore.
+    first_click_safe: bool,
+    /// Mode of [`Termsweeper::hardcore`], the boards after this one get no first-click
+    /// safety either.
+    hardcore: bool,
+    zen: bool,
+    symbols: SymbolSet,
+    numbers: NumberGlyphs,
+    theme: Theme,
+    flood_fill: FloodFillPolicy,
+    flag_mines_on_win: bool,
+    chord_on_reveal: bool,
+    chord_on_flag: bool,
+    /// See [`Settings::max_flood_per_click`].
+    max_flood_per_click: u16,
+    /// See [`Settings::danger_shading`].
+    danger_shading: bool,
+    animate_flood_fill: bool,
+    show_flag_count: bool,
+    show_cursor_position: bool,
+    /// See [`Settings::show_key_bar`], toggled with `?`.
+    show_key_bar: bool,
+    checkerboard: bool,
+    /// See [`Settings::dot_empty_fields`].
+    dot_empty_fields: bool,
+    /// See [`Settings::reveal_guard`].
+    reveal_guard: u16,
+    /// See [`Settings::confirm_first_reveal`].
+    confirm_first_reveal: bool,
+    /// See [`Settings::enter_reveals`].
+    enter_reveals: bool,
+    /// Field a guarded reveal waits to be confirmed for.
+    pending_reveal: Option<(u8, u8)>,
+    /// Set by Shift+C until the next key confirms or cancels removing every flag.
+    clear_flags_pending: bool,
+    /// See [`Settings::celebrate_win`].
+    celebrate_win: bool,
+    /// Start of the victory animation of a won game.
+    celebration: Option<Instant>,
+    /// See [`Settings::dim_satisfied_numbers`].
+    dim_satisfied: bool,
+    /// See [`Settings::count_adjacent_flags`].
+    count_adjacent_flags: bool,
+    /// See [`Settings::clear_when_flagged`].
+    clear_when_flagged: bool,
+    /// See [`Settings::square_fields`].
+    square_fields: bool,
+    /// See [`Settings::jumbo_fields`].
+    jumbo_fields: bool,
+    /// See [`Settings::cursor_style`].
+    cursor_style: CursorStyle,
+    /// See [`Settings::limit_flags`].
+    limit_flags: bool,
+    /// See [`Settings::show_flag_gauge`].
+    show_flag_gauge: bool,
+    /// Reveals and flags the player made, see [`Termsweeper::click`].
+    clicks: u32,
+    /// See [`Settings::analyze_losses`].
+    analyze_losses: bool,
+    /// See [`Settings::blink_mines_on_loss`].
+    blink_mines_on_loss: bool,
+    /// What the numbers proved right before the fatal reveal of a lost game.
+    loss_analysis: Option<LossAnalysis>,
+    /// Fields that have to be left to reveal after the first reveal, `0` accepts any board.
+    min_fields_after_first_reveal: u16,
+    /// Fields the first reveal has to open, `0` accepts any board.
+    min_opening: u16,
+    flood_animation: Option<FloodAnimation>,
+    /// Custom mine placement, `None` scatters the mines from a fresh seed kept in `seed`.
+    generator: Option<Rc<dyn BoardGenerator>>,
+    /// Where a board scattered from a fresh seed came from.
+    seed: Option<BoardSeed>,
+    cell_renderer: Rc<dyn CellRenderer>,
+    /// How far numbers look for mines, `1` for the standard 3×3 neighbourhood and `2` for
+    /// the 5×5 variant. Also decides which fields a zero opens.
+    neighbor_radius: u8,
+    /// See [`Settings::self_inclusive_counts`].
+    self_inclusive_counts: bool,
+    x_ray: bool,
+    /// Whether flags are colored by their correctness, see [`RenderOptions::flag_assist`].
+    flag_assist: bool,
+    /// Set once a hint, peek, the flag assist, danger shading or x-ray was used, such games
+    /// don't count for the stats.
+    assisted: bool,
     game_state: GameState,
+    finished_at: Option<Instant>,
+    shaken_at: Option<Instant>,
+    /// Last input, for revealing the field under a resting cursor.
+    dwelling_since: Option<Instant>,
+    timer: Stopwatch,
+    paused: bool,
+    obscured: bool,
+    command_input: Option<String>,
+    /// Status line text and its color, like the explanation of a hint, shown until the
+    /// next input.
+    message: Option<(String, Color)>,
+    /// When the message disappears on its own, see [`Termsweeper::set_status`].
+    message_until: Option<Instant>,
+    /// Lives a game starts with, see [`Settings::lives`].
+    lives: u8,
+    lives_left: u8,
+    /// Peeks a game starts with.
+    peeks: u8,
+    peeks_left: u8,
+    /// The last peeked field and when it was peeked.
+    peek: Option<((u8, u8), Instant)>,
+    /// Fields collected to be marked or revealed together.
+    selection: Vec<(u8, u8)>,
+    /// Opposite corner of a box spanned to the cursor, see [`Termsweeper::box_fields`].
+    box_anchor: Option<(u8, u8)>,
+    /// The latest moves of this game, oldest first.
+    history: VecDeque<Move>,
+    show_history: bool,
+    /// Moves the history panel is scrolled back from the latest one.
+    history_scroll: usize,
+    board_cache: RefCell<BoardCache>,
 }
 
-impl Termsweeper {
-    pub fn default() -> Termsweeper {
-        Self::new(45, 18, 75)
-    }
+/// Time from `elapsed` to the next multiple of `interval`.
+fn until_next(elapsed: Duration, interval: Duration) -> Duration {
+    interval - Duration::from_nanos((elapsed.as_nanos() % interval.as_nanos()) as u64)
+}
 
-    pub fn new(columns: u8, rows: u8, number_of_mines: u16) -> Termsweeper {
-        Termsweeper {
-            columns,
-            rows,
-            number_of_mines,
-            fields_left_to_reveal: 0,
-            board: vec![Row::new(columns); rows.into()],
-            cursor: (0, 0),
-            initialized: false,
-            game_state: GameState::Playing,
-        }
+/// Scatters confetti over the fields in `layout`, thinning out towards the end of the
+/// victory animation. Every frame is seeded by its number, so redrawing within a frame
+/// keeps the confetti in place. Grid lines stay untouched.
+fn render_confetti(elapsed: Duration, layout: &[Vec<(Rect, Borders)>], buf: &mut Buffer) {
+    if elapsed >= CELEBRATION_DURATION {
+        return;
     }
-
-    fn initialize(&mut self) {
-        if !self.initialized {
-            let valid_adjacent = self.get_valid_adjacent_fields(self.cursor);
-            let max_mines =
-                self.columns as u16 * self.rows as u16 - 1 - valid_adjacent.len() as u16;
-            if self.number_of_mines > max_mines {
-                self.number_of_mines = max_mines;
-            }
-            let mut mine_locations: Vec<(u8, u8)> = vec![];
-            let mut rng = rand::thread_rng();
-            let mut i: u16 = 0;
-            self.fields_left_to_reveal =
-                self.columns as u16 * self.rows as u16 - self.number_of_mines;
-            while i < self.number_of_mines {
-                let row = rng.gen_range(0..self.rows);
-                let column = rng.gen_range(0..self.columns);
-                if (row, column) != self.cursor
-                    && !valid_adjacent.contains(&(row, column))
-                    && !mine_locations.contains(&(row, column))
-                {
-                    mine_locations.push((row, column));
-                    i += 1;
-                }
-            }
-            for mine_location in mine_locations {
-                self.get_field_mut(mine_location).is_mine = true;
-            }
-            for row_index in 0..self.rows {
-                for column_index in 0..self.columns {
-                    let current_field_location = (row_index, column_index);
-                    for location in self.get_valid_adjacent_fields((row_index, column_index)) {
-                        if self.get_field(location).is_mine {
-                            self.get_field_mut(current_field_location).adjacent_mines += 1;
-                        }
-                    }
+    let frame = elapsed.as_millis() / CELEBRATION_INTERVAL.as_millis();
+    let density = 0.4 * (1.0 - elapsed.as_secs_f64() / CELEBRATION_DURATION.as_secs_f64());
+    let mut rng = StdRng::seed_from_u64(frame as u64);
+    for (field_area, _) in layout.iter().flatten() {
+        for y in field_area.top()..field_area.bottom() {
+            for x in field_area.left()..field_area.right() {
+                if !rng.gen_bool(density) {
+                    continue;
                 }
+                let glyph = CONFETTI_GLYPHS[rng.gen_range(0..CONFETTI_GLYPHS.len())];
+                let color = CONFETTI_COLORS[rng.gen_range(0..CONFETTI_COLORS.len())];
+                buf.get_mut(x, y)
+                    .set_char(glyph)
+                    .set_style(Style::default().fg(color).bold());
             }
-            self.initialized = true
         }
     }
+}
 
-    pub fn handle_event(&mut self, key: KeyEvent) -> bool {
-        match self.game_state {
-            GameState::Playing => match key.code {
-                KeyCode::Char('h') | KeyCode::Left => self.move_cursor_left(),
-                KeyCode::Char('j') | KeyCode::Down => self.move_cursor_down(),
-                KeyCode::Char('k') | KeyCode::Up => self.move_cursor_up(),
-                KeyCode::Char('l') | KeyCode::Right => self.move_cursor_right(),
-                KeyCode::Char('m') | KeyCode::Enter => self.toggle_mark(),
-                KeyCode::Char(' ') => self.reveal(),
-                _ => false,
-            },
-            _ => false,
-        }
+/// `count` as a subscript digit, `₊` beyond nine.
+fn subscript(count: u8) -> char {
+    match count {
+        0..=9 => char::from_u32('₀' as u32 + count as u32).unwrap_or('₊'),
+        _ => '₊',
     }
+}
 
-    fn get_field(&self, location: (u8, u8)) -> &Field {
-        &self.board[location.0 as usize].fields[location.1 as usize]
-    }
+/// Where [`Termsweeper::save_game`] writes games, `None` without a data directory.
+pub fn games_directory() -> Option<PathBuf> {
+    dirs::data_dir().map(|directory| directory.join("termsweeper").join("games"))
+}
 
-    fn get_field_mut(&mut self, location: (u8, u8)) -> &mut Field {
-        &mut self.board[location.0 as usize].fields[location.1 as usize]
+/// Deletes every saved game, see [`delete_by_renaming`].
+pub fn delete_saved_games() -> io::Result<()> {
+    games_directory().map_or(Ok(()), |directory| delete_by_renaming(&directory))
+}
+
+/// Deletes the file or directory at `path`, a missing one is already gone. It is first
+/// renamed out of the way in one step, so an interrupted deletion never leaves it partly
+/// deleted in its place.
+pub fn delete_by_renaming(path: &Path) -> io::Result<()> {
+    let deleted = path.with_extension("deleted");
+    match fs::rename(path, &deleted) {
+        Err(error) if error.kind() == io::ErrorKind::NotFound => (),
+        result => result?,
+    }
+    // Also finishes the removal left behind by an earlier interrupted reset.
+    let removed = if deleted.is_dir() {
+        fs::remove_dir_all(&deleted)
+    } else {
+        fs::remove_file(&deleted)
+    };
+    match removed {
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
     }
+}
 
-    fn get_valid_adjacent_fields(&self, location: (u8, u8)) -> Vec<(u8, u8)> {
-        self.get_ordered_adjacent_fields(location)
-            .to_vec()
-            .into_iter()
-            .flatten()
-            .collect()
+/// Where the game in progress is autosaved, among the saved games so a reset deletes it
+/// with those.
+fn autosave_path() -> Option<PathBuf> {
+    games_directory().map(|directory| directory.join("autosave.txt"))
+}
+
+pub fn has_autosave() -> bool {
+    autosave_path().is_some_and(|path| path.exists())
+}
+
+/// Replaces the autosave with `text` from [`Termsweeper::autosave`]. It is written next to
+/// the old one and renamed over it, so a crash while writing keeps the previous autosave.
+pub fn write_autosave(text: &str) -> io::Result<()> {
+    let Some(path) = autosave_path() else {
+        return Ok(());
+    };
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)?;
     }
+    let written = path.with_extension("tmp");
+    fs::write(&written, text)?;
+    fs::rename(written, path)
+}
 
-    fn get_ordered_adjacent_fields(&self, location: (u8, u8)) -> [Option<(u8, u8)>; 8] {
-        let mut return_values: [Option<(u8, u8)>; 8] = [None; 8];
-        let column_index = location.1;
-        let row_index = location.0;
-        let left_field_index = column_index.checked_sub(1);
-        let right_field_index = column_index + 1;
-        let top_row_index = row_index.checked_sub(1);
-        let bottowm_row_index = row_index + 1;
-        match left_field_index {
-            Some(left_column_value) => {
-                return_values[0] = Some((row_index, left_column_value));
-                return_values[1] = match top_row_index {
-                    Some(top_row_value) => Some((top_row_value, left_column_value)),
-                    None => None,
-                };
+pub fn read_autosave() -> io::Result<String> {
+    match autosave_path() {
+        Some(path) => fs::read_to_string(path),
+        None => Err(io::Error::new(io::ErrorKind::NotFound, "no data directory")),
+    }
+}
 
-                return_values[2] = if bottowm_row_index < self.rows {
-                    Some((bottowm_row_index, left_column_value))
-                } else {
-                    None
-                }
-            }
-            None => (),
-        }
-        return_values[3] = match top_row_index {
-            Some(top_row_value) => Some((top_row_value, column_index)),
-            None => None,
-        };
-        if bottowm_row_index < self.rows {
-            return_values[4] = Some((bottowm_row_index, column_index));
-        }
-        if right_field_index < self.columns {
-            return_values[5] = Some((row_index, right_field_index));
-            return_values[6] = match top_row_index {
-                Some(top_row_value) => Some((top_row_value, right_field_index)),
-                None => None,
-            };
-            if bottowm_row_index < self.rows {
-                return_values[7] = Some((bottowm_row_index, right_field_index));
-            }
-        }
-        return_values
+pub fn delete_autosave() -> io::Result<()> {
+    let Some(path) = autosave_path() else {
+        return Ok(());
+    };
+    match fs::remove_file(path) {
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
     }
+}
 
-    fn move_cursor_left(&mut self) -> bool {
-        if self.cursor.1 != 0 {
-            self.cursor.1 -= 1;
-            true
-        } else {
-            false
-        }
+/// Formats a duration as `m:ss` or `h:mm:ss`, optionally with tenths of a second.
+pub fn format_duration(duration: Duration, tenths: bool) -> String {
+    let seconds = duration.as_secs();
+    let mut formatted = match seconds / 3600 {
+        0 => format!("{}:{:02}", seconds / 60, seconds % 60),
+        hours => format!("{hours}:{:02}:{:02}", seconds / 60 % 60, seconds % 60),
+    };
+    if tenths {
+        formatted += &format!(".{}", duration.subsec_millis() / 100);
     }
+    formatted
+}
 
-    fn move_cursor_down(&mut self) -> bool {
-        if self.cursor.0 != self.rows - 1 {
-            self.cursor.0 += 1;
-            true
-        } else {
-            false
+/// Formats a count with thousands separators, e.g. `12,345`.
+pub fn format_count(count: i64) -> String {
+    let digits = count.unsigned_abs().to_string();
+    let mut formatted = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    if count < 0 {
+        formatted.insert(0, '-');
+    }
+    formatted
+}
+
+/// Parses a 1-based `row,column` pair as typed into the command line. Numbers too large
+/// for any board still parse, to be clamped to the board.
+fn parse_coordinate(input: &str) -> Option<(u32, u32)> {
+    let (row, column) = input.split_once(',')?;
+    Some((row.trim().parse().ok()?, column.trim().parse().ok()?))
+}
+
+impl Termsweeper {
+    /// Board with the dimensions and options from `settings`.
+    pub fn from_settings(settings: &Settings) -> Termsweeper {
+        let mut game = Self::new(settings.columns, settings.rows, settings.number_of_mines);
+        game.neighbor_radius = settings.neighbor_radius.clamp(1, 2);
+        game.self_inclusive_counts = settings.self_inclusive_counts;
+        game.peeks = settings.peeks;
+        game.peeks_left = settings.peeks;
+        game.lives = settings.lives.max(1);
+        game.lives_left = game.lives;
+        game.apply_settings(settings);
+        game
+    }
+
+    /// Board without first-click safety: mines are placed up front, so the very first
+    /// reveal can already hit one.
+    pub fn hardcore(settings: &Settings) -> Termsweeper {
+        let mut game = Self::from_settings(settings);
+        game.first_click_safe = false;
+        game.hardcore = true;
+        game.initialize();
+        game
+    }
+
+    /// Board that can't be lost: revealing a mine is refused with a short shake of the
+    /// cursor instead of ending the game.
+    pub fn zen(settings: &Settings) -> Termsweeper {
+        let mut game = Self::from_settings(settings);
+        game.zen = true;
+        game
+    }
+
+    /// Board with the mines of a text grid, one line per row with `*` for a mine and `.`
+    /// for a safe field. Like hardcore boards the mines are in place from the start.
+    pub fn from_layout(layout: &str, settings: &Settings) -> Result<Termsweeper, String> {
+        let lines: Vec<&str> = layout.trim().lines().map(str::trim_end).collect();
+        let Some(columns) = lines.first().map(|line| line.chars().count()) else {
+            return Err("The layout is empty".to_string());
+        };
+        if lines.len() > u8::MAX as usize || columns > u8::MAX as usize {
+            return Err(format!(
+                "The layout can have at most {} rows and columns",
+                u8::MAX
+            ));
+        }
+        if lines.len() < MIN_SIDE as usize || columns < MIN_SIDE as usize {
+            return Err(format!(
+                "The layout needs at least {MIN_SIDE} rows and columns"
+            ));
+        }
+        let mut mines = vec![];
+        for (row, line) in lines.iter().enumerate() {
+            if line.chars().count() != columns {
+                return Err(format!(
+                    "Row {} has {} fields instead of {columns}",
+                    row + 1,
+                    line.chars().count()
+                ));
+            }
+            for (column, character) in line.chars().enumerate() {
+                match character {
+                    '*' => mines.push((row as u8, column as u8)),
+                    '.' => (),
+                    _ => {
+                        return Err(format!(
+                            "Unexpected '{character}' at ({},{}), use '*' for mines and '.' \
+                             for safe fields",
+                            row + 1,
+                            column + 1
+                        ))
+                    }
+                }
+            }
+        }
+        if mines.is_empty() {
+            return Err("The layout needs at least one mine".to_string());
+        }
+        if mines.len() == lines.len() * columns {
+            return Err("The layout needs at least one safe field".to_string());
+        }
+        let mut game = Self::from_settings(&Settings {
+            columns: columns as u8,
+            rows: lines.len() as u8,
+            number_of_mines: mines.len() as u16,
+            ..settings.clone()
+        });
+        game.first_click_safe = false;
+        for location in mines {
+            game.get_field_mut(location).is_mine = true;
+        }
+        game.count_adjacent_mines();
+        game.fields_left_to_reveal = game.columns as u16 * game.rows as u16 - game.number_of_mines;
+        game.initialized = true;
+        debug_assert_eq!(game.validate(), Ok(()));
+        Ok(game)
+    }
+
+    /// Game in progress from the text of [`Termsweeper::autosave`], carrying on with the
+    /// time, clicks, lives and peeks it had. The history of moves starts over.
+    pub fn from_autosave(text: &str, settings: &Settings) -> Result<Termsweeper, String> {
+        let mut lines = text.lines();
+        if lines.next() != Some("termsweeper-autosave 1") {
+            return Err("The autosave is in an unknown format".to_string());
+        }
+        let mut values = BTreeMap::new();
+        for line in lines.by_ref().take_while(|line| *line != "fields") {
+            let value = line
+                .split_once(' ')
+                .and_then(|(key, value)| Some((key, value.parse::<u64>().ok()?)));
+            let Some((key, value)) = value else {
+                return Err(format!("Unexpected '{line}' in the autosave"));
+            };
+            values.insert(key, value);
+        }
+        let value = |key: &str| {
+            values
+                .get(key)
+                .copied()
+                .ok_or_else(|| format!("The autosave has no {key}"))
+        };
+        let rows: Vec<&str> = lines.collect();
+        let mut layout = String::new();
+        for row in &rows {
+            for symbol in row.chars() {
+                layout.push(match symbol {
+                    '*' | 'x' | 'F' | 'M' => '*',
+                    '.' | 'o' | 'f' | 'm' => '.',
+                    _ => return Err(format!("Unexpected '{symbol}' in the autosave")),
+                });
+            }
+            layout.push('\n');
+        }
+        let mut game = Self::from_layout(
+            &layout,
+            &Settings {
+                neighbor_radius: value("radius")? as u8,
+                // Missing from autosaves written before the variant.
+                self_inclusive_counts: values
+                    .get("self_inclusive")
+                    .is_some_and(|value| *value != 0),
+                ..settings.clone()
+            },
+        )?;
+        for (row, symbols) in rows.iter().enumerate() {
+            for (column, symbol) in symbols.chars().enumerate() {
+                if symbol == 'o' {
+                    game.fields_left_to_reveal -= 1;
+                }
+                let field = game.get_field_mut((row as u8, column as u8));
+                match symbol {
+                    'o' => field.revealed = true,
+                    'x' => {
+                        field.exploded = true;
+                        field.marked = true;
+                    }
+                    'f' | 'F' => field.marked = true,
+                    'm' | 'M' => field.maybe = true,
+                    _ => (),
+                }
+            }
+        }
+        // The resumed board comes with its mines like an imported one, the boards after it
+        // are generated in the mode it was played in.
+        game.zen = value("zen")? != 0;
+        game.hardcore = value("hardcore")? != 0;
+        game.assisted = value("assisted")? != 0;
+        game.clicks = value("clicks")? as u32;
+        game.lives_left = (value("lives")? as u8).clamp(1, game.lives);
+        game.peeks_left = (value("peeks")? as u8).min(game.peeks);
+        game.timer.elapsed = Duration::from_millis(value("time")?);
+        Ok(game)
+    }
+
+    /// Board of `columns` by `rows` fields with `number_of_mines` mines. Sides shorter than
+    /// [`MIN_SIDE`] are lengthened to it and the mines clamped to between one and one less
+    /// than the fields, so every board can be drawn, lost and won.
+    pub fn new(columns: u8, rows: u8, number_of_mines: u16) -> Termsweeper {
+        let columns = columns.max(MIN_SIDE);
+        let rows = rows.max(MIN_SIDE);
+        let number_of_mines = number_of_mines.clamp(1, columns as u16 * rows as u16 - 1);
+        Termsweeper {
+            columns,
+            rows,
+            number_of_mines,
+            fields_left_to_reveal: 0,
+            board: vec![Row::new(columns); rows.into()],
+            cursor: (0, 0),
+            initialized: false,
+            first_click_safe: true,
+            hardcore: false,
+            zen: false,
+            symbols: SymbolSet::Ascii,
+            numbers: NumberGlyphs::default(),
+            theme: Theme::default(),
+            flood_fill: FloodFillPolicy::default(),
+            flag_mines_on_win: false,
+            chord_on_reveal: false,
+            chord_on_flag: false,
+            max_flood_per_click: 0,
+            danger_shading: false,
+            animate_flood_fill: false,
+            show_flag_count: false,
+            show_cursor_position: false,
+            show_key_bar: true,
+            checkerboard: false,
+            dot_empty_fields: false,
+            reveal_guard: 0,
+            confirm_first_reveal: false,
+            enter_reveals: false,
+            pending_reveal: None,
+            clear_flags_pending: false,
+            celebrate_win: true,
+            celebration: None,
+            dim_satisfied: false,
+            count_adjacent_flags: false,
+            clear_when_flagged: false,
+            square_fields: false,
+            jumbo_fields: false,
+            cursor_style: CursorStyle::Background,
+            limit_flags: false,
+            show_flag_gauge: false,
+            clicks: 0,
+            analyze_losses: true,
+            blink_mines_on_loss: false,
+            loss_analysis: None,
+            min_fields_after_first_reveal: 0,
+            min_opening: 0,
+            flood_animation: None,
+            generator: None,
+            seed: None,
+            cell_renderer: Rc::new(DefaultCellRenderer),
+            neighbor_radius: 1,
+            self_inclusive_counts: false,
+            x_ray: false,
+            flag_assist: false,
+            assisted: false,
+            game_state: GameState::Playing,
+            finished_at: None,
+            shaken_at: None,
+            dwelling_since: Some(Instant::now()),
+            timer: Stopwatch::default(),
+            paused: false,
+            obscured: false,
+            command_input: None,
+            message: None,
+            message_until: None,
+            lives: 1,
+            lives_left: 1,
+            peeks: 0,
+            peeks_left: 0,
+            peek: None,
+            selection: vec![],
+            box_anchor: None,
+            history: VecDeque::new(),
+            show_history: false,
+            history_scroll: 0,
+            board_cache: RefCell::new(BoardCache::default()),
+        }
+    }
+
+    /// A fresh game with the same dimensions, mode and options. With `same_board` the mines
+    /// stay where they were, to retry the board just played.
+    pub fn restart(&self, same_board: bool) -> Termsweeper {
+        let mut game = self.fresh(self.columns, self.rows, self.number_of_mines);
+        if same_board && self.initialized {
+            for (row, original) in game.board.iter_mut().zip(&self.board) {
+                for (field, original) in row.fields.iter_mut().zip(&original.fields) {
+                    field.is_mine = original.is_mine;
+                    field.adjacent_mines = original.adjacent_mines;
+                }
+            }
+            game.fields_left_to_reveal =
+                self.columns as u16 * self.rows as u16 - self.number_of_mines;
+            game.initialized = true;
+            game.seed = self.seed;
+            game.first_click_safe = self.first_click_safe;
+        } else if self.hardcore {
+            game.initialize();
+        }
+        game
+    }
+
+    /// A fresh game with the same mode and options on a board of another size.
+    pub fn resized(&self, columns: u8, rows: u8, number_of_mines: u16) -> Termsweeper {
+        let mut game = self.fresh(columns, rows, number_of_mines);
+        if self.hardcore {
+            game.initialize();
+        }
+        game
+    }
+
+    /// Board of the given size in the mode and with the options of this game, the mines
+    /// not placed yet.
+    fn fresh(&self, columns: u8, rows: u8, number_of_mines: u16) -> Termsweeper {
+        let mut game = Self::new(columns, rows, number_of_mines);
+        game.first_click_safe = !self.hardcore;
+        game.hardcore = self.hardcore;
+        game.zen = self.zen;
+        game.symbols = self.symbols;
+        game.numbers = self.numbers.clone();
+        game.theme = self.theme;
+        game.flood_fill = self.flood_fill;
+        game.flag_mines_on_win = self.flag_mines_on_win;
+        game.chord_on_reveal = self.chord_on_reveal;
+        game.chord_on_flag = self.chord_on_flag;
+        game.max_flood_per_click = self.max_flood_per_click;
+        game.danger_shading = self.danger_shading;
+        game.assisted = self.danger_shading;
+        game.animate_flood_fill = self.animate_flood_fill;
+        game.show_flag_count = self.show_flag_count;
+        game.show_cursor_position = self.show_cursor_position;
+        game.show_key_bar = self.show_key_bar;
+        game.checkerboard = self.checkerboard;
+        game.dot_empty_fields = self.dot_empty_fields;
+        game.reveal_guard = self.reveal_guard;
+        game.confirm_first_reveal = self.confirm_first_reveal;
+        game.enter_reveals = self.enter_reveals;
+        game.celebrate_win = self.celebrate_win;
+        game.dim_satisfied = self.dim_satisfied;
+        game.count_adjacent_flags = self.count_adjacent_flags;
+        game.clear_when_flagged = self.clear_when_flagged;
+        game.square_fields = self.square_fields;
+        game.jumbo_fields = self.jumbo_fields;
+        game.cursor_style = self.cursor_style;
+        game.limit_flags = self.limit_flags;
+        game.show_flag_gauge = self.show_flag_gauge;
+        game.analyze_losses = self.analyze_losses;
+        game.blink_mines_on_loss = self.blink_mines_on_loss;
+        game.min_fields_after_first_reveal = self.min_fields_after_first_reveal;
+        game.min_opening = self.min_opening;
+        game.generator = self.generator.clone();
+        game.cell_renderer = self.cell_renderer.clone();
+        game.neighbor_radius = self.neighbor_radius;
+        game.self_inclusive_counts = self.self_inclusive_counts;
+        game.peeks = self.peeks;
+        game.peeks_left = self.peeks;
+        game.lives = self.lives;
+        game.lives_left = self.lives;
+        game
+    }
+
+    /// Columns, rows and mines of the largest board the game screen can show in a terminal
+    /// of `width` × `height` with the field look of `settings`, with mines at
+    /// [`AUTO_FIT_DENSITY`].
+    pub fn fitting_size(settings: &Settings, width: u16, height: u16) -> (u8, u8, u16) {
+        let game = Self::from_settings(settings);
+        let options = game.render_options();
+        // The outer border takes two columns, the last column and row have no separator.
+        let fit = |space: u16, size: u16| {
+            ((space + 1) / size).clamp(MIN_SIDE as u16, u8::MAX as u16) as u8
+        };
+        let columns = fit(width.saturating_sub(2), options.field_size());
+        let rows = fit(
+            height.saturating_sub(game.frame_height()),
+            options.row_size(),
+        );
+        let mines = (columns as f64 * rows as f64 * AUTO_FIT_DENSITY).round() as u16;
+        (columns, rows, mines)
+    }
+
+    /// Mines the board is played with. Boards too small for the requested mines get as
+    /// many as fit around a safe first reveal anywhere on the board, so the count is known
+    /// before the first reveal and doesn't change with it. Every board gets at least one
+    /// mine, see [`Termsweeper::safe_area`].
+    fn mine_count(&self) -> u16 {
+        let side = 2 * self.neighbor_radius + 1;
+        // At least one field has to stay free of mines for the game to be winnable.
+        let safe_fields = if self.first_click_safe {
+            side.min(self.columns) as u16 * side.min(self.rows) as u16
+        } else {
+            1
+        };
+        let max_mines = (self.columns as u16 * self.rows as u16).saturating_sub(safe_fields);
+        self.number_of_mines.min(max_mines.max(1))
+    }
+
+    /// Fields first-click safety keeps free of mines, the first reveal and its
+    /// neighbourhood. Boards where the neighbourhood leaves no room for the mines only keep
+    /// the first reveal itself free, rather than starting with nothing to find.
+    fn safe_area(&self) -> Vec<(u8, u8)> {
+        let mut area = self.get_valid_adjacent_fields(self.cursor);
+        area.push(self.cursor);
+        if area.len() as u16 + self.number_of_mines > self.columns as u16 * self.rows as u16 {
+            area = vec![self.cursor];
+        }
+        area
+    }
+
+    fn initialize(&mut self) {
+        if !self.initialized {
+            self.number_of_mines = self.mine_count();
+            let mut excluded = vec![];
+            if self.first_click_safe {
+                excluded = self.safe_area();
+            }
+            let fields = self.columns as u16 * self.rows as u16;
+            self.fields_left_to_reveal = fields - self.number_of_mines;
+            // Keeping the ring around the safe neighbourhood free as well makes large
+            // openings likely, as long as the mines still fit.
+            if self.first_click_safe && self.min_opening > 0 {
+                let wider = self.get_ordered_adjacent_fields(self.cursor, 2 * self.neighbor_radius);
+                let mut wider: Vec<_> = wider.into_iter().flatten().collect();
+                wider.push(self.cursor);
+                if wider.len() as u16 + self.number_of_mines <= fields {
+                    excluded = wider;
+                }
+            }
+            // Boards where the first reveal opens or leaves too little are placed again.
+            // When the minimums can't be met the last attempt is played anyway.
+            for _ in 0..GENERATION_ATTEMPTS {
+                self.place_mines(&excluded);
+                if !self.first_click_safe
+                    || (self.min_fields_after_first_reveal == 0 && self.min_opening == 0)
+                {
+                    break;
+                }
+                let opened = self.flood_region(self.cursor).len() as u16 + 1;
+                if opened >= self.min_opening
+                    && self.fields_left_to_reveal - opened >= self.min_fields_after_first_reveal
+                {
+                    break;
+                }
+            }
+            self.initialized = true;
+            debug_assert_eq!(self.validate(), Ok(()));
+        }
+    }
+
+    /// Checks what a freshly placed board has to hold, to catch generators placing mines
+    /// wrongly early: as many mines as the board has, every number matching the mines
+    /// around it, no mine in the [`Termsweeper::safe_area`] on first-click-safe boards and
+    /// every safe field left to reveal. Returns the first broken invariant.
+    fn validate(&self) -> Result<(), String> {
+        let mines = self
+            .board
+            .iter()
+            .flat_map(|row| &row.fields)
+            .filter(|field| field.is_mine)
+            .count() as u16;
+        if mines != self.number_of_mines {
+            return Err(format!(
+                "{mines} mines placed instead of {}",
+                self.number_of_mines
+            ));
+        }
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                let neighbours = self.get_valid_adjacent_fields((row, column));
+                let counted = neighbours
+                    .into_iter()
+                    .filter(|&location| self.get_field(location).is_mine)
+                    .count() as u8
+                    + (self.self_inclusive_counts && self.get_field((row, column)).is_mine) as u8;
+                let number = self.get_field((row, column)).adjacent_mines;
+                if number != counted {
+                    return Err(format!(
+                        "({},{}) shows {number} instead of {counted} mines",
+                        row + 1,
+                        column + 1
+                    ));
+                }
+            }
+        }
+        if self.first_click_safe {
+            if let Some((row, column)) = self
+                .safe_area()
+                .into_iter()
+                .find(|&location| self.get_field(location).is_mine)
+            {
+                return Err(format!(
+                    "Mine at ({},{}) around the first reveal",
+                    row + 1,
+                    column + 1
+                ));
+            }
+        }
+        let safe_fields = self.columns as u16 * self.rows as u16 - self.number_of_mines;
+        if self.fields_left_to_reveal != safe_fields {
+            return Err(format!(
+                "{} fields left to reveal instead of {safe_fields}",
+                self.fields_left_to_reveal
+            ));
+        }
+        Ok(())
+    }
+
+    /// Places the mines from the generator outside of `excluded` and counts them for every
+    /// field.
+    fn place_mines(&mut self, excluded: &[(u8, u8)]) {
+        for row in &mut self.board {
+            for field in &mut row.fields {
+                field.is_mine = false;
+                field.adjacent_mines = 0;
+            }
+        }
+        let (columns, rows, mines) = (self.columns, self.rows, self.number_of_mines);
+        let mine_locations = match &self.generator {
+            Some(generator) => generator.generate(columns, rows, mines, excluded),
+            None => {
+                let seed = BoardSeed {
+                    seed: rand::thread_rng().gen(),
+                    first_reveal: self.cursor,
+                };
+                self.seed = Some(seed);
+                SeededGenerator { seed: seed.seed }.generate(columns, rows, mines, excluded)
+            }
+        };
+        for mine_location in mine_locations {
+            self.get_field_mut(mine_location).is_mine = true;
+        }
+        self.count_adjacent_mines();
+    }
+
+    fn count_adjacent_mines(&mut self) {
+        for row_index in 0..self.rows {
+            for column_index in 0..self.columns {
+                let current_field_location = (row_index, column_index);
+                if self.self_inclusive_counts && self.get_field(current_field_location).is_mine {
+                    self.get_field_mut(current_field_location).adjacent_mines += 1;
+                }
+                for location in self.get_valid_adjacent_fields((row_index, column_index)) {
+                    if self.get_field(location).is_mine {
+                        self.get_field_mut(current_field_location).adjacent_mines += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Covered fields the flood fill opens around `origin` when it has no adjacent mines,
+    /// following the flood fill policy, nearest first. `origin` itself isn't included.
+    /// Zeros spread to every field their numbers count, diagonals included, so zero areas
+    /// touching only at a corner open together.
+    fn flood_region(&self, origin: (u8, u8)) -> Vec<(u8, u8)> {
+        let mut region = vec![];
+        let mut visited = BTreeSet::new();
+        if self.get_field(origin).adjacent_mines != 0 {
+            return region;
+        }
+        let mut adjacent_fields = VecDeque::from(self.get_valid_adjacent_fields(origin));
+        while let Some(location) = adjacent_fields.pop_front() {
+            let field = self.get_field(location);
+            if location == origin
+                || field.revealed
+                || !visited.insert(location)
+                || (self.flood_fill.respect_flags && field.marked)
+                || (!self.flood_fill.reveal_borders && field.adjacent_mines != 0)
+            {
+                continue;
+            }
+            region.push(location);
+            if field.adjacent_mines == 0 {
+                adjacent_fields.extend(self.get_valid_adjacent_fields(location));
+            }
+        }
+        region
+    }
+
+    pub fn handle_event(&mut self, key: KeyEvent) -> bool {
+        self.dwelling_since = Some(Instant::now());
+        self.message = None;
+        self.message_until = None;
+        if !self.is_reveal_key(key) {
+            self.pending_reveal = None;
+        }
+        if key.code != KeyCode::Char('C') {
+            self.clear_flags_pending = false;
+        }
+        if let Some(animation) = self.flood_animation.take() {
+            let dirty = &mut self.board_cache.get_mut().dirty;
+            dirty.extend(animation.waves.keys());
+            // Any key but quit only skips to the end of a running animation.
+            if animation.is_running() && key.code != KeyCode::Char('q') {
+                return true;
+            }
+        }
+        // Like the flood fill, the victory animation only ends early on a key.
+        if let Some(started) = self.celebration.take() {
+            if started.elapsed() < CELEBRATION_DURATION && key.code != KeyCode::Char('q') {
+                return true;
+            }
+        }
+        if self.command_input.is_some() {
+            return self.handle_command_input(key);
+        }
+        match key.code {
+            KeyCode::Char('H') => {
+                self.show_history = !self.show_history;
+                self.history_scroll = 0;
+                return true;
+            }
+            KeyCode::Char('?') => {
+                self.show_key_bar = !self.show_key_bar;
+                return true;
+            }
+            KeyCode::PageUp | KeyCode::Char('[') if self.show_history => {
+                self.history_scroll =
+                    (self.history_scroll + 1).min(self.history.len().saturating_sub(1));
+                return true;
+            }
+            KeyCode::PageDown | KeyCode::Char(']') if self.show_history => {
+                self.history_scroll = self.history_scroll.saturating_sub(1);
+                return true;
+            }
+            _ => (),
+        }
+        match self.game_state {
+            GameState::Playing => {
+                let previous_box = self.box_fields();
+                let handled = self.handle_playing_key(key);
+                // The box preview follows the cursor, so both its old and new fields change.
+                let current_box = self.box_fields();
+                let dirty = &mut self.board_cache.get_mut().dirty;
+                dirty.extend(previous_box);
+                dirty.extend(current_box);
+                handled
+            }
+            _ => false,
+        }
+    }
+
+    /// Space, and Enter when it reveals instead of marking.
+    fn is_reveal_key(&self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Char(' ') => true,
+            KeyCode::Enter => self.enter_reveals,
+            _ => false,
+        }
+    }
+
+    /// The reveal keys for the key bar and prompts, see [`Termsweeper::is_reveal_key`].
+    fn reveal_keys(&self) -> &'static str {
+        if self.enter_reveals {
+            "Space/Enter"
+        } else {
+            "Space"
+        }
+    }
+
+    fn handle_playing_key(&mut self, key: KeyEvent) -> bool {
+        if self.is_reveal_key(key) {
+            return self.click(Self::guarded_reveal).handled();
+        }
+        match key.code {
+            KeyCode::Left if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                self.jump_cursor((0, -1))
+            }
+            KeyCode::Down if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                self.jump_cursor((1, 0))
+            }
+            KeyCode::Up if key.modifiers.contains(KeyModifiers::SHIFT) => self.jump_cursor((-1, 0)),
+            KeyCode::Right if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                self.jump_cursor((0, 1))
+            }
+            KeyCode::Char('h') | KeyCode::Left => self.move_cursor_left(),
+            KeyCode::Char('j') | KeyCode::Down => self.move_cursor_down(),
+            KeyCode::Char('k') | KeyCode::Up => self.move_cursor_up(),
+            KeyCode::Char('l') | KeyCode::Right => self.move_cursor_right(),
+            KeyCode::Char('m') | KeyCode::Enter => self.click(Self::toggle_mark).handled(),
+            KeyCode::Char('u') => self.toggle_maybe(),
+            KeyCode::Char('i') => self.show_hint(),
+            KeyCode::Char('o') => self.show_mine_probability(),
+            KeyCode::Char('v') => self.check_flags(),
+            KeyCode::Char('C') => self.clear_flags(),
+            KeyCode::Char('p') => self.peek(),
+            KeyCode::Char('V') => {
+                self.flag_assist = !self.flag_assist;
+                self.assisted = true;
+                true
+            }
+            KeyCode::Char('x') => self.toggle_selected(),
+            KeyCode::Char('M') => self.mark_selection(),
+            KeyCode::Char('R') => self.click(Self::reveal_selection).handled(),
+            KeyCode::Char('b') => self.toggle_box(),
+            KeyCode::Esc if self.box_anchor.is_some() => {
+                self.box_anchor = None;
+                true
+            }
+            KeyCode::Esc if !self.selection.is_empty() => {
+                for location in std::mem::take(&mut self.selection) {
+                    self.board_cache.get_mut().dirty.insert(location);
+                }
+                true
+            }
+            KeyCode::F(12) if CHEATS_ENABLED => {
+                self.x_ray = !self.x_ray;
+                self.assisted = true;
+                true
+            }
+            KeyCode::F(9) if CHEATS_ENABLED => self.cheat_win(),
+            KeyCode::Char(':') => {
+                self.command_input = Some(String::new());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn handle_command_input(&mut self, key: KeyEvent) -> bool {
+        let Some(input) = &mut self.command_input else {
+            return false;
+        };
+        match key.code {
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                if input.pop().is_none() {
+                    self.command_input = None;
+                }
+            }
+            KeyCode::Enter => {
+                let input = self.command_input.take().unwrap_or_default();
+                match parse_coordinate(&input) {
+                    Some((row, column)) => {
+                        let clamp = |value: u32| value.saturating_sub(1).min(u8::MAX.into()) as u8;
+                        self.set_cursor(clamp(row), clamp(column));
+                    }
+                    None if input.trim().is_empty() => (),
+                    None => self.set_status(
+                        format!("Can't go to '{input}', type a row and a column like 3,12"),
+                        Color::LightRed,
+                        STATUS_DURATION,
+                    ),
+                }
+            }
+            KeyCode::Esc => self.command_input = None,
+            _ => return false,
+        }
+        true
+    }
+
+    /// The cursor location as zero-based `(row, column)`.
+    pub fn cursor_position(&self) -> (u8, u8) {
+        self.cursor
+    }
+
+    pub fn is_revealed(&self, location: (u8, u8)) -> bool {
+        self.get_field(location).revealed
+    }
+
+    /// Whether `location` carries a flag, revealed fields don't.
+    pub fn is_flagged(&self, location: (u8, u8)) -> bool {
+        let field = self.get_field(location);
+        field.marked && !field.revealed
+    }
+
+    /// Applies the display and rule options of `settings`, both for new and running games.
+    pub fn apply_settings(&mut self, settings: &Settings) {
+        self.symbols = settings.symbols;
+        self.numbers = settings.numbers.clone();
+        self.theme = settings.theme;
+        self.flood_fill = settings.flood_fill;
+        self.flag_mines_on_win = settings.flag_mines_on_win;
+        self.chord_on_reveal = settings.chord_on_reveal;
+        self.chord_on_flag = settings.chord_on_flag;
+        self.max_flood_per_click = settings.max_flood_per_click;
+        if self.danger_shading != settings.danger_shading {
+            self.danger_shading = settings.danger_shading;
+            self.assisted |= settings.danger_shading;
+            self.invalidate_board();
+        }
+        self.animate_flood_fill = settings.animate_flood_fill;
+        self.show_flag_count = settings.show_flag_count;
+        self.show_cursor_position = settings.show_cursor_position;
+        self.show_key_bar = settings.show_key_bar;
+        self.checkerboard = settings.checkerboard;
+        self.dot_empty_fields = settings.dot_empty_fields;
+        self.reveal_guard = settings.reveal_guard;
+        self.confirm_first_reveal = settings.confirm_first_reveal;
+        self.enter_reveals = settings.enter_reveals;
+        self.celebrate_win = settings.celebrate_win;
+        self.dim_satisfied = settings.dim_satisfied_numbers;
+        self.count_adjacent_flags = settings.count_adjacent_flags;
+        self.clear_when_flagged = settings.clear_when_flagged;
+        self.square_fields = settings.square_fields;
+        self.jumbo_fields = settings.jumbo_fields;
+        self.cursor_style = settings.cursor_style;
+        self.limit_flags = settings.limit_flags;
+        self.show_flag_gauge = settings.show_flag_gauge;
+        self.analyze_losses = settings.analyze_losses;
+        self.blink_mines_on_loss = settings.blink_mines_on_loss;
+        self.min_fields_after_first_reveal = settings.min_fields_after_first_reveal;
+        self.min_opening = settings.min_opening;
+    }
+
+    /// Reveals the field under the cursor once it has rested there for `threshold`, so the
+    /// game can be played with movement input alone. Fires once per resting period, and
+    /// like Space asks for a second one where the reveal has to be confirmed.
+    pub fn dwell(&mut self, threshold: Duration) -> bool {
+        match self.dwell_deadline(threshold) {
+            Some(deadline) if deadline <= Instant::now() => {
+                self.dwelling_since = None;
+                self.click(Self::guarded_reveal).handled()
+            }
+            _ => false,
+        }
+    }
+
+    /// When [`Termsweeper::dwell`] will reveal the field under the resting cursor, if at all.
+    pub fn dwell_deadline(&self, threshold: Duration) -> Option<Instant> {
+        match self.dwelling_since {
+            Some(since)
+                if matches!(self.game_state, GameState::Playing)
+                    && !self.paused
+                    && self.command_input.is_none() =>
+            {
+                Some(since + threshold)
+            }
+            _ => None,
+        }
+    }
+
+    /// Places the cursor for the start of the game, falling back to the top-left corner
+    /// when there is no previous position.
+    pub fn place_cursor(&mut self, start: StartPosition, last_used: Option<(u8, u8)>) {
+        let (row, column) = match (start, last_used) {
+            (StartPosition::Center, _) => (self.rows / 2, self.columns / 2),
+            (StartPosition::LastUsed, Some(location)) => location,
+            _ => (0, 0),
+        };
+        self.set_cursor(row, column);
+    }
+
+    /// Moves the cursor to the zero-based `(row, column)`, clamping it to the board.
+    pub fn set_cursor(&mut self, row: u8, column: u8) {
+        self.cursor = (row.min(self.rows - 1), column.min(self.columns - 1));
+    }
+
+    /// Drops the cached board so the next frame draws every field again.
+    pub fn invalidate_board(&mut self) {
+        self.board_cache.get_mut().frame = None;
+    }
+
+    fn get_field(&self, location: (u8, u8)) -> &Field {
+        &self.board[location.0 as usize].fields[location.1 as usize]
+    }
+
+    fn get_field_mut(&mut self, location: (u8, u8)) -> &mut Field {
+        self.board_cache.get_mut().dirty.insert(location);
+        &mut self.board[location.0 as usize].fields[location.1 as usize]
+    }
+
+    /// Fields within `neighbor_radius` rows and columns of `location` that are on the board.
+    fn get_valid_adjacent_fields(&self, location: (u8, u8)) -> Vec<(u8, u8)> {
+        self.get_ordered_adjacent_fields(location, self.neighbor_radius)
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// The `(2 * radius + 1)² - 1` fields around `location` row by row, `None` for those
+    /// off the board.
+    fn get_ordered_adjacent_fields(&self, location: (u8, u8), radius: u8) -> Vec<Option<(u8, u8)>> {
+        let radius = radius as i16;
+        let mut return_values = vec![];
+        for row_offset in -radius..=radius {
+            for column_offset in -radius..=radius {
+                if (row_offset, column_offset) == (0, 0) {
+                    continue;
+                }
+                let row_index = location.0 as i16 + row_offset;
+                let column_index = location.1 as i16 + column_offset;
+                return_values.push(
+                    ((0..self.rows as i16).contains(&row_index)
+                        && (0..self.columns as i16).contains(&column_index))
+                    .then_some((row_index as u8, column_index as u8)),
+                );
+            }
+        }
+        return_values
+    }
+
+    fn move_cursor_left(&mut self) -> bool {
+        if self.cursor.1 != 0 {
+            self.cursor.1 -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn move_cursor_down(&mut self) -> bool {
+        if self.cursor.0 != self.rows - 1 {
+            self.cursor.0 += 1;
+            true
+        } else {
+            false
         }
     }
 
@@ -299,71 +1933,1159 @@ impl Termsweeper {
         }
     }
 
-    fn move_cursor_right(&mut self) -> bool {
-        if self.cursor.1 != self.columns - 1 {
-            self.cursor.1 += 1;
-            true
-        } else {
-            false
+    fn move_cursor_right(&mut self) -> bool {
+        if self.cursor.1 != self.columns - 1 {
+            self.cursor.1 += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Moves the cursor in `direction` to the nearest field that is neither revealed nor
+    /// flagged, staying put when there is none.
+    fn jump_cursor(&mut self, direction: (i16, i16)) -> bool {
+        let (mut row, mut column) = (self.cursor.0 as i16, self.cursor.1 as i16);
+        loop {
+            row += direction.0;
+            column += direction.1;
+            if !(0..self.rows as i16).contains(&row) || !(0..self.columns as i16).contains(&column)
+            {
+                return false;
+            }
+            let field = self.get_field((row as u8, column as u8));
+            if !field.revealed && !field.marked {
+                self.cursor = (row as u8, column as u8);
+                return true;
+            }
+        }
+    }
+
+    /// Notes or clears the covered field under the cursor as uncertain, replacing a flag.
+    fn toggle_maybe(&mut self) -> bool {
+        let field = self.get_field(self.cursor);
+        if field.revealed || field.exploded {
+            return false;
+        }
+        let flagged = field.marked;
+        let field = self.get_field_mut(self.cursor);
+        field.maybe = !field.maybe;
+        field.marked = false;
+        if flagged {
+            self.record(Action::Unflag, self.cursor);
+        }
+        true
+    }
+
+    fn toggle_mark(&mut self) -> ClickResult {
+        if !self.get_field(self.cursor).revealed && !self.get_field(self.cursor).exploded {
+            if !self.get_field(self.cursor).marked && !self.can_flag() {
+                // Handled to show why nothing happened, without counting as a click.
+                return ClickResult::Refused;
+            }
+            self.get_field_mut(self.cursor).marked = !self.get_field(self.cursor).marked;
+            self.get_field_mut(self.cursor).maybe = false;
+            let action = if self.get_field(self.cursor).marked {
+                Action::Flag
+            } else {
+                Action::Unflag
+            };
+            self.record(action, self.cursor);
+            if self.chord_on_flag && matches!(action, Action::Flag) {
+                for location in self.get_valid_adjacent_fields(self.cursor) {
+                    if !matches!(self.game_state, GameState::Playing) {
+                        break;
+                    }
+                    self.chord(location);
+                }
+            }
+            if self.clear_when_flagged
+                && self.initialized
+                && matches!(self.game_state, GameState::Playing)
+                && self.flags_match_mines()
+            {
+                self.clear_unflagged();
+            }
+            ClickResult::Acted
+        } else {
+            ClickResult::Ignored
+        }
+    }
+
+    /// Flags on covered fields, including the mines exploded at the cost of a life.
+    fn flag_count(&self) -> u16 {
+        self.board
+            .iter()
+            .flat_map(|row| &row.fields)
+            .filter(|field| field.marked && !field.revealed)
+            .count() as u16
+    }
+
+    /// Whether another flag may be placed, telling why not in the status line when
+    /// [`Settings::limit_flags`] keeps it from.
+    fn can_flag(&mut self) -> bool {
+        if !self.limit_flags || self.flag_count() < self.mine_count() {
+            return true;
+        }
+        self.message = Some((
+            format!(
+                "All {} flags are placed, remove one to flag elsewhere",
+                format_count(self.mine_count() as i64)
+            ),
+            Color::Yellow,
+        ));
+        false
+    }
+
+    /// Removes every flag from the covered fields once a second Shift+C confirms it, the
+    /// mines exploded at the cost of a life stay flagged.
+    fn clear_flags(&mut self) -> bool {
+        let flagged: Vec<(u8, u8)> = (0..self.rows)
+            .flat_map(|row| (0..self.columns).map(move |column| (row, column)))
+            .filter(|location| {
+                let field = self.get_field(*location);
+                field.marked && !field.revealed && !field.exploded
+            })
+            .collect();
+        if !matches!(self.game_state, GameState::Playing) || flagged.is_empty() {
+            return false;
+        }
+        if !std::mem::take(&mut self.clear_flags_pending) {
+            self.clear_flags_pending = true;
+            self.message = Some((
+                format!(
+                    "Press Shift+C again to remove all {} flags, any other key keeps them",
+                    format_count(flagged.len() as i64)
+                ),
+                Color::Yellow,
+            ));
+            return true;
+        }
+        for location in flagged {
+            self.get_field_mut(location).marked = false;
+            self.record(Action::Unflag, location);
+        }
+        true
+    }
+
+    /// Whether every mine is flagged, or exploded, and no flag is on a safe covered field.
+    fn flags_match_mines(&self) -> bool {
+        self.board
+            .iter()
+            .flat_map(|row| &row.fields)
+            .all(|field| field.is_mine == (field.marked && (!field.revealed || field.is_mine)))
+    }
+
+    /// Reveals every safe field left and wins the game.
+    fn clear_unflagged(&mut self) {
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                let field = self.get_field((row, column));
+                if !field.is_mine && !field.revealed {
+                    self.get_field_mut((row, column)).revealed = true;
+                }
+            }
+        }
+        self.fields_left_to_reveal = 0;
+        self.finish(GameState::Won);
+    }
+
+    fn reveal(&mut self) -> ClickResult {
+        if !self.initialized {
+            self.initialize();
+        }
+        if !self.zen {
+            self.timer.start();
+        }
+        let field = self.get_field(self.cursor);
+        if field.revealed && field.adjacent_mines == 0 && self.max_flood_per_click != 0 {
+            return self.flood(self.cursor).into();
+        }
+        if self.chord_on_reveal && field.revealed {
+            return self.chord(self.cursor).into();
+        }
+        if !self.get_field(self.cursor).marked && !self.get_field(self.cursor).revealed {
+            if self.zen && self.get_field(self.cursor).is_mine {
+                self.shaken_at = Some(Instant::now());
+                return ClickResult::Refused;
+            }
+            if self.get_field(self.cursor).is_mine && self.lives_left > 1 {
+                self.lives_left -= 1;
+                let field = self.get_field_mut(self.cursor);
+                field.exploded = true;
+                field.marked = true;
+                self.record(Action::Reveal, self.cursor);
+                self.shaken_at = Some(Instant::now());
+                self.set_status(
+                    format!("Hit a mine, {} left", self.lives_left_label()),
+                    Color::LightRed,
+                    STATUS_DURATION,
+                );
+                return ClickResult::Acted;
+            }
+            if self.analyze_losses && self.get_field(self.cursor).is_mine {
+                self.analyze_loss();
+            }
+            self.get_field_mut(self.cursor).revealed = true;
+            self.record(Action::Reveal, self.cursor);
+            if self.get_field(self.cursor).is_mine {
+                self.finish(GameState::GameOver);
+            } else {
+                self.fields_left_to_reveal -= 1;
+                self.flood(self.cursor);
+            }
+            ClickResult::Acted
+        } else {
+            ClickResult::Ignored
+        }
+    }
+
+    /// Reveals the flood fill region around the revealed `origin`, at most
+    /// `max_flood_per_click` fields of it, and checks for a win.
+    fn flood(&mut self, origin: (u8, u8)) -> bool {
+        let mut region = self.flood_region(origin);
+        if self.max_flood_per_click != 0 {
+            region.truncate(self.max_flood_per_click as usize);
+        }
+        let mut waves = BTreeMap::new();
+        for location in region {
+            self.open(origin, location, &mut waves);
+        }
+        if self.flood_fill.through_numbers && self.max_flood_per_click == 0 && !waves.is_empty() {
+            self.flood_through_numbers(origin, &mut waves);
+        }
+        let revealed = !waves.is_empty();
+        if self.animate_flood_fill && revealed {
+            self.flood_animation = Some(FloodAnimation {
+                started: Instant::now(),
+                last_wave: waves.values().copied().max().unwrap_or(0),
+                waves,
+            });
+        }
+        if self.fields_left_to_reveal == 0 {
+            self.finish(GameState::Won);
+        }
+        revealed
+    }
+
+    /// Reveals `location` as part of the flood fill from `origin`, noting its ring in `waves`.
+    fn open(&mut self, origin: (u8, u8), location: (u8, u8), waves: &mut BTreeMap<(u8, u8), u16>) {
+        self.get_field_mut(location).revealed = true;
+        self.fields_left_to_reveal -= 1;
+        let ring = location
+            .0
+            .abs_diff(origin.0)
+            .max(location.1.abs_diff(origin.1));
+        waves.insert(location, ring as u16);
+    }
+
+    /// Carries the flood fill from `origin` on past its numbered border: covered fields next
+    /// to the opened area that the numbers prove safe are revealed, flooding on from zeros,
+    /// until nothing more follows. Flags are left out of the deductions, so a wrong flag
+    /// can't lead to a mine.
+    fn flood_through_numbers(&mut self, origin: (u8, u8), waves: &mut BTreeMap<(u8, u8), u16>) {
+        loop {
+            let next: Vec<(u8, u8)> =
+                solver::forced_deductions(&self.numbers_view(), self.neighbor_radius)
+                    .safe
+                    .into_iter()
+                    .filter(|location| {
+                        !(self.flood_fill.respect_flags && self.get_field(*location).marked)
+                            && self
+                                .get_valid_adjacent_fields(*location)
+                                .iter()
+                                .any(|neighbour| {
+                                    *neighbour == origin || waves.contains_key(neighbour)
+                                })
+                    })
+                    .collect();
+            if next.is_empty() {
+                return;
+            }
+            for location in next {
+                if self.get_field(location).revealed {
+                    continue;
+                }
+                self.open(origin, location, waves);
+                for flooded in self.flood_region(location) {
+                    self.open(origin, flooded, waves);
+                }
+            }
+        }
+    }
+
+    /// Reveals the field under the cursor, unless few fields are left and the deduction
+    /// rules can't prove it safe, or it is a first reveal to confirm. Then it takes a second
+    /// press to reveal it.
+    fn guarded_reveal(&mut self) -> ClickResult {
+        let confirmed = self.pending_reveal.take() == Some(self.cursor);
+        let field = self.get_field(self.cursor);
+        if self.confirm_first_reveal && !self.initialized && !confirmed && !field.marked {
+            self.pending_reveal = Some(self.cursor);
+            self.message = Some((
+                format!(
+                    "Press {} again to start the board at ({},{})",
+                    self.reveal_keys(),
+                    self.cursor.0 + 1,
+                    self.cursor.1 + 1
+                ),
+                Color::Yellow,
+            ));
+            return ClickResult::Refused;
+        }
+        let risky = self.initialized
+            && !confirmed
+            && !field.revealed
+            && !field.marked
+            && self.fields_left_to_reveal <= self.reveal_guard
+            && !solver::forced_deductions(&self.player_view(), self.neighbor_radius)
+                .safe
+                .contains(&self.cursor);
+        if !risky {
+            return self.reveal();
+        }
+        self.pending_reveal = Some(self.cursor);
+        self.message = Some((
+            format!(
+                "({},{}) isn't provably safe with {} fields left, press {} again to reveal it",
+                self.cursor.0 + 1,
+                self.cursor.1 + 1,
+                self.fields_left_to_reveal,
+                self.reveal_keys()
+            ),
+            Color::Yellow,
+        ));
+        ClickResult::Refused
+    }
+
+    /// Reveals the covered neighbours of the number at `origin` once as many of them are
+    /// flagged as the number says. Wrong flags make this hit a mine. Returns whether any
+    /// field was revealed.
+    fn chord(&mut self, origin: (u8, u8)) -> bool {
+        if !self.get_field(origin).revealed {
+            return false;
+        }
+        let number = self.get_field(origin).adjacent_mines;
+        let neighbours = self.get_valid_adjacent_fields(origin);
+        let flags = neighbours
+            .iter()
+            .filter(|location| self.get_field(**location).marked)
+            .count();
+        if number == 0 || flags != number as usize {
+            return false;
+        }
+        let cursor = self.cursor;
+        let mut revealed = false;
+        for location in neighbours {
+            let field = self.get_field(location);
+            if field.revealed || field.marked {
+                continue;
+            }
+            self.cursor = location;
+            revealed |= self.reveal() == ClickResult::Acted;
+            if !matches!(self.game_state, GameState::Playing) {
+                break;
+            }
+        }
+        self.cursor = cursor;
+        revealed
+    }
+
+    fn lives_left_label(&self) -> String {
+        match self.lives_left {
+            1 => "1 life".to_string(),
+            lives => format!("{lives} lives"),
+        }
+    }
+
+    /// Runs a reveal or flag the player asked for, counting it for the fewest clicks records
+    /// when it changes the board. Fields a single click opens through flood fills or chords
+    /// don't add to the count, and neither do prompts like the reveal guard's.
+    fn click(&mut self, action: fn(&mut Termsweeper) -> ClickResult) -> ClickResult {
+        let playing = matches!(self.game_state, GameState::Playing);
+        let result = action(self);
+        if result == ClickResult::Acted && playing {
+            self.clicks += 1;
+        }
+        result
+    }
+
+    fn record(&mut self, action: Action, location: (u8, u8)) {
+        if self.history.len() == MAX_HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(Move {
+            at: Instant::now(),
+            action,
+            location,
+        });
+    }
+
+    /// Shows the content of the covered field under the cursor for a moment without
+    /// revealing it, as long as peeks are left. Peeking at a mine is harmless.
+    fn peek(&mut self) -> bool {
+        if self.peeks_left == 0 || !self.initialized || self.get_field(self.cursor).revealed {
+            return false;
+        }
+        self.peeks_left -= 1;
+        self.assisted = true;
+        if let Some((previous, _)) = self.peek {
+            self.board_cache.get_mut().dirty.insert(previous);
+        }
+        self.peek = Some((self.cursor, Instant::now()));
+        true
+    }
+
+    /// Adds the field under the cursor to the selection or removes it again.
+    fn toggle_selected(&mut self) -> bool {
+        if self.get_field(self.cursor).revealed {
+            return false;
+        }
+        match self
+            .selection
+            .iter()
+            .position(|location| *location == self.cursor)
+        {
+            Some(index) => {
+                self.selection.remove(index);
+            }
+            None => self.selection.push(self.cursor),
+        }
+        self.board_cache.get_mut().dirty.insert(self.cursor);
+        true
+    }
+
+    /// Flags every selected field and clears the selection.
+    fn mark_selection(&mut self) -> bool {
+        if self.selection.is_empty() {
+            return false;
+        }
+        for location in std::mem::take(&mut self.selection) {
+            if !self.get_field(location).revealed
+                && !self.get_field(location).marked
+                && self.can_flag()
+            {
+                self.get_field_mut(location).marked = true;
+                self.record(Action::Flag, location);
+            }
+            self.board_cache.get_mut().dirty.insert(location);
+        }
+        true
+    }
+
+    /// Starts a box at the cursor, or adds the covered fields of the current box to the
+    /// selection, where they can be revealed or marked together.
+    fn toggle_box(&mut self) -> bool {
+        if self.box_anchor.is_none() {
+            self.box_anchor = Some(self.cursor);
+            return true;
+        }
+        for location in self.box_fields() {
+            let field = self.get_field(location);
+            if !field.revealed && !field.marked && !self.selection.contains(&location) {
+                self.selection.push(location);
+            }
+        }
+        self.box_anchor = None;
+        true
+    }
+
+    /// Fields in the rectangle between the box anchor and the cursor, row by row.
+    fn box_fields(&self) -> Vec<(u8, u8)> {
+        let Some(anchor) = self.box_anchor else {
+            return vec![];
+        };
+        let rows = anchor.0.min(self.cursor.0)..=anchor.0.max(self.cursor.0);
+        let columns = anchor.1.min(self.cursor.1)..=anchor.1.max(self.cursor.1);
+        rows.flat_map(|row| columns.clone().map(move |column| (row, column)))
+            .collect()
+    }
+
+    /// Reveals the selected fields in the order they were selected and clears the
+    /// selection. The first mine among them stops the batch, the fields after it stay
+    /// covered: it ends the game like a single reveal, or costs a life or shakes the cursor
+    /// where those apply.
+    fn reveal_selection(&mut self) -> ClickResult {
+        if self.selection.is_empty() {
+            return ClickResult::Ignored;
+        }
+        let mut result = ClickResult::Refused;
+        let cursor = self.cursor;
+        for location in std::mem::take(&mut self.selection) {
+            self.board_cache.get_mut().dirty.insert(location);
+            let field = self.get_field(location);
+            // Opened by an earlier field of the batch, revealing it again would chord.
+            if field.revealed {
+                continue;
+            }
+            let hits_mine = field.is_mine && !field.marked;
+            self.cursor = location;
+            if self.reveal() == ClickResult::Acted {
+                result = ClickResult::Acted;
+            }
+            if hits_mine || !matches!(self.game_state, GameState::Playing) {
+                break;
+            }
+        }
+        self.cursor = cursor;
+        result
+    }
+
+    /// Notes whether the numbers proved some field safe before the mine under the cursor
+    /// is revealed. Flags are left out, a wrong flag proves nothing.
+    fn analyze_loss(&mut self) {
+        let analysis = match solver::safe_hint(&self.numbers_view(), self.neighbor_radius) {
+            Some(hint) => {
+                self.board_cache.get_mut().dirty.insert(hint.location);
+                LossAnalysis {
+                    safe: Some(hint.location),
+                    explanation: hint.reason,
+                }
+            }
+            None => LossAnalysis {
+                safe: None,
+                explanation: "No field was provably safe, the loss was a forced guess".to_string(),
+            },
+        };
+        self.loss_analysis = Some(analysis);
+    }
+
+    /// Moves the cursor to a deducible field and explains why it is deducible.
+    fn show_hint(&mut self) -> bool {
+        if !self.initialized {
+            return false;
+        }
+        self.message = Some(
+            match solver::hint(&self.player_view(), self.neighbor_radius) {
+                Some(hint) => {
+                    self.assisted = true;
+                    self.set_cursor(hint.location.0, hint.location.1);
+                    let color = if hint.is_mine {
+                        Color::LightRed
+                    } else {
+                        Color::LightGreen
+                    };
+                    (hint.reason, color)
+                }
+                None => (
+                    "Nothing can be deduced, a guess is required".to_string(),
+                    Color::Yellow,
+                ),
+            },
+        );
+        true
+    }
+
+    /// Shows the chance of a mine under the cursor in the status line, from the numbers
+    /// around it or else the share of mines among the covered fields. Flags are left out
+    /// like in the loss analysis, so a wrong one can't skew it.
+    fn show_mine_probability(&mut self) -> bool {
+        let field = self.get_field(self.cursor);
+        if !self.initialized || field.revealed || field.exploded {
+            return false;
+        }
+        self.assisted = true;
+        let (row, column) = (self.cursor.0 + 1, self.cursor.1 + 1);
+        let probability =
+            solver::mine_probability(&self.numbers_view(), self.neighbor_radius, self.cursor);
+        let text = match probability {
+            Some(probability) => {
+                format!("P(mine) ≈ {:.0}% at ({row},{column})", probability * 100.0)
+            }
+            None => {
+                let fields = self.board.iter().flat_map(|row| &row.fields);
+                let covered = fields
+                    .clone()
+                    .filter(|field| !field.revealed && !field.exploded)
+                    .count();
+                let mines = fields
+                    .filter(|field| field.is_mine && !field.exploded)
+                    .count();
+                format!(
+                    "P(mine) ≈ – at ({row},{column}), {:.0}% of the covered fields are mines",
+                    mines as f64 * 100.0 / covered.max(1) as f64
+                )
+            }
+        };
+        self.message = Some((text, Color::LightCyan));
+        true
+    }
+
+    /// Counts correct and wrong flags and unflagged mines as
+    /// `(correct_flags, incorrect_flags, missed_mines)`.
+    fn flag_check(&self) -> (u16, u16, u16) {
+        let mut counts = (0, 0, 0);
+        for field in self.board.iter().flat_map(|row| &row.fields) {
+            if field.exploded {
+                continue;
+            }
+            match (field.marked, field.is_mine) {
+                (true, true) => counts.0 += 1,
+                (true, false) => counts.1 += 1,
+                (false, true) => counts.2 += 1,
+                (false, false) => (),
+            }
+        }
+        counts
+    }
+
+    fn flag_summary(&self) -> String {
+        let (correct, incorrect, missed) = self.flag_check();
+        format!("Flags: {correct} correct, {incorrect} wrong, {missed} mines unflagged")
+    }
+
+    /// Tells how many flags are right without telling which, for playing by deduction
+    /// alone.
+    fn check_flags(&mut self) -> bool {
+        if !self.initialized {
+            return false;
+        }
+        self.message = Some((self.flag_summary(), Color::LightBlue));
+        true
+    }
+
+    /// Flags every mine and reveals everything else, to exercise the win path quickly.
+    fn cheat_win(&mut self) -> bool {
+        // Like x-ray, so the cheated win stays out of the stats and the gallery.
+        self.assisted = true;
+        self.initialize();
+        for row in &mut self.board {
+            for field in &mut row.fields {
+                field.marked = field.is_mine;
+                field.revealed = !field.is_mine;
+            }
+        }
+        self.fields_left_to_reveal = 0;
+        self.finish(GameState::Won);
+        true
+    }
+
+    fn finish(&mut self, game_state: GameState) {
+        self.game_state = game_state;
+        self.finished_at = Some(Instant::now());
+        self.timer.stop();
+        if self.celebrate_win && matches!(self.game_state, GameState::Won) {
+            self.celebration = Some(Instant::now());
+        }
+        if self.flag_mines_on_win && matches!(self.game_state, GameState::Won) {
+            self.flag_all_mines();
+        } else {
+            self.reveal_all();
+        }
+    }
+
+    /// Outcome of a finished game for the stats, `None` while playing, for zen games which
+    /// can't be lost, for games with extra lives and for games that used an assist.
+    pub fn result(&self) -> Option<stats::GameResult> {
+        let won = match self.game_state {
+            GameState::Playing => return None,
+            _ if self.zen || self.lives > 1 || self.assisted => return None,
+            GameState::Won => true,
+            GameState::GameOver => false,
+        };
+        Some(stats::GameResult {
+            won,
+            board: self.board_key(),
+            time: self.timer.elapsed(),
+            clicks: self.clicks,
+        })
+    }
+
+    fn board_key(&self) -> String {
+        stats::board_key(
+            self.columns,
+            self.rows,
+            self.mine_count(),
+            self.neighbor_radius,
+        )
+    }
+
+    /// Stops the timer while the player is away, optionally hiding the board so the pause
+    /// can't be used to think.
+    pub fn pause(&mut self, obscure: bool) {
+        if self.timer.is_running() {
+            self.timer.stop();
+            self.paused = true;
+            self.obscured = obscure;
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if self.paused {
+            self.timer.start();
+            self.dwelling_since = Some(Instant::now());
+            self.paused = false;
+            self.obscured = false;
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Reveals the field at `location` like Space would there, for driving a game without
+    /// key presses. Returns whether anything changed, nothing does off the board.
+    pub fn reveal_at(&mut self, location: (u8, u8)) -> bool {
+        if !matches!(self.game_state, GameState::Playing) || !self.contains(location) {
+            return false;
+        }
+        self.cursor = location;
+        self.click(Self::reveal) == ClickResult::Acted
+    }
+
+    /// Flags or unflags the field at `location` like M would there.
+    pub fn mark_at(&mut self, location: (u8, u8)) -> bool {
+        if !matches!(self.game_state, GameState::Playing) || !self.contains(location) {
+            return false;
+        }
+        self.cursor = location;
+        self.click(Self::toggle_mark) == ClickResult::Acted
+    }
+
+    /// Whether `location` is a field of the board.
+    fn contains(&self, location: (u8, u8)) -> bool {
+        location.0 < self.rows && location.1 < self.columns
+    }
+
+    /// The board as the player sees it, one string per field: `?` for covered fields, `F`
+    /// for flags, `*` for exploded mines and mines shown after the game and the count of
+    /// adjacent mines for revealed fields.
+    pub fn snapshot(&self) -> Vec<Vec<String>> {
+        self.board
+            .iter()
+            .map(|row| {
+                row.fields
+                    .iter()
+                    .map(|field| {
+                        if field.exploded {
+                            "*".to_string()
+                        } else if field.marked && (!field.revealed || field.is_mine) {
+                            "F".to_string()
+                        } else if !field.revealed {
+                            "?".to_string()
+                        } else if field.is_mine {
+                            "*".to_string()
+                        } else {
+                            field.adjacent_mines.to_string()
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Columns, rows and mines of the board.
+    pub fn dimensions(&self) -> (u8, u8, u16) {
+        (self.columns, self.rows, self.mine_count())
+    }
+
+    /// Whether the game is still going, won or lost, for anything driving a game without
+    /// looking at the screen.
+    pub fn outcome(&self) -> Outcome {
+        match self.game_state {
+            GameState::Playing => Outcome::InProgress,
+            GameState::Won => Outcome::Won,
+            GameState::GameOver => Outcome::Lost,
+        }
+    }
+
+    /// Whether the game was won or lost. The board no longer takes input then.
+    pub fn is_finished(&self) -> bool {
+        self.outcome() != Outcome::InProgress
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.outcome() == Outcome::Won
+    }
+
+    /// Whether a reveal or flag was made, so leaving the board gives up a game in progress.
+    pub fn is_started(&self) -> bool {
+        self.clicks > 0
+    }
+
+    pub fn is_timer_running(&self) -> bool {
+        self.timer.is_running()
+    }
+
+    /// Time until the game screen changes without input: the next pulse of the end-of-game
+    /// flash, a victory confetti frame or a zen shake, or the next full second on the
+    /// timer. `None` when nothing is running.
+    pub fn next_frame(&self) -> Option<Duration> {
+        let mut frames = vec![];
+        if let Some(finished_at) = self.finished_at {
+            if finished_at.elapsed() < FLASH_DURATION {
+                frames.push(until_next(finished_at.elapsed(), FLASH_INTERVAL));
+            }
+        }
+        if let Some(started) = self.celebration {
+            if started.elapsed() < CELEBRATION_DURATION {
+                frames.push(until_next(started.elapsed(), CELEBRATION_INTERVAL));
+            }
+        }
+        if let Some(shaken_at) = self.shaken_at {
+            if shaken_at.elapsed() < SHAKE_DURATION {
+                frames.push(until_next(shaken_at.elapsed(), SHAKE_INTERVAL));
+            }
+        }
+        if let Some(animation) = self.flood_animation.as_ref().filter(|a| a.is_running()) {
+            frames.push(until_next(animation.started.elapsed(), FLOOD_WAVE_INTERVAL));
+        }
+        if let Some((_, peeked_at)) = self.peek {
+            if peeked_at.elapsed() < PEEK_DURATION {
+                frames.push(PEEK_DURATION - peeked_at.elapsed());
+            }
+        }
+        if let Some(until) = self.message_until {
+            frames.extend(until.checked_duration_since(Instant::now()));
+        }
+        if self.timer.is_running() {
+            frames.push(until_next(self.timer.elapsed(), Duration::from_secs(1)));
+        }
+        frames.into_iter().min()
+    }
+
+    /// Whether the revealed number at `location` has as many flags or exploded mines around
+    /// it as it counts.
+    fn is_satisfied(&self, location: (u8, u8)) -> bool {
+        let field = self.get_field(location);
+        field.revealed
+            && field.adjacent_mines > 0
+            && self.adjacent_flags(location) == field.adjacent_mines
+    }
+
+    /// Flags and exploded mines around `location`.
+    fn adjacent_flags(&self, location: (u8, u8)) -> u8 {
+        self.get_valid_adjacent_fields(location)
+            .into_iter()
+            .map(|neighbour| self.get_field(neighbour))
+            .filter(|neighbour| neighbour.marked && (!neighbour.revealed || neighbour.is_mine))
+            .count() as u8
+    }
+
+    /// A rough danger between `0` and `1` for covered fields next to numbers: the largest
+    /// share of missing mines to covered fields among their numbered neighbours. Fields
+    /// without a chance of a mine by that measure are left out.
+    fn danger_scores(&self) -> BTreeMap<(u8, u8), f32> {
+        let mut scores = BTreeMap::new();
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                let field = self.get_field((row, column));
+                if !field.revealed || field.is_mine || field.adjacent_mines == 0 {
+                    continue;
+                }
+                let neighbours = self.get_valid_adjacent_fields((row, column));
+                let flags = neighbours
+                    .iter()
+                    .filter(|location| self.get_field(**location).marked)
+                    .count();
+                let covered: Vec<_> = neighbours
+                    .into_iter()
+                    .filter(|location| {
+                        let field = self.get_field(*location);
+                        !field.revealed && !field.marked
+                    })
+                    .collect();
+                let missing = (field.adjacent_mines as usize).saturating_sub(flags);
+                if covered.is_empty() || missing == 0 {
+                    continue;
+                }
+                let score = (missing as f32 / covered.len() as f32).min(1.0);
+                for location in covered {
+                    let entry = scores.entry(location).or_insert(score);
+                    *entry = entry.max(score);
+                }
+            }
+        }
+        scores
+    }
+
+    fn cursor_color(&self) -> Color {
+        match self.shaken_at {
+            Some(shaken_at) if shaken_at.elapsed() < SHAKE_DURATION => {
+                let phase = shaken_at.elapsed().as_millis() / SHAKE_INTERVAL.as_millis();
+                if phase.is_multiple_of(2) {
+                    Color::LightYellow
+                } else {
+                    self.theme.cursor
+                }
+            }
+            _ => self.theme.cursor,
+        }
+    }
+
+    /// Draws the bracket cursor over the separators beside the field at `field_area`, on
+    /// the row of its text. Drawn over the copied frame, so the separators the neighbours
+    /// share stay untouched in the board cache.
+    fn render_cursor_brackets(&self, field_area: Rect, borders: Borders, buf: &mut Buffer) {
+        let content_width = field_area
+            .width
+            .saturating_sub(borders.contains(Borders::RIGHT) as u16);
+        let content_height = field_area
+            .height
+            .saturating_sub(borders.contains(Borders::BOTTOM) as u16);
+        let y = field_area.y + content_height.saturating_sub(1) / 2;
+        let style = Style::default().fg(self.cursor_color()).bold();
+        let left = field_area.x.checked_sub(1);
+        let right = Some(field_area.x + content_width);
+        for (x, bracket) in [(left, '['), (right, ']')] {
+            if let Some(x) = x.filter(|x| (buf.area.left()..buf.area.right()).contains(x)) {
+                buf.get_mut(x, y).set_char(bracket).set_style(style);
+            }
+        }
+    }
+
+    /// Bar of the `flags` placed against the mines for the header, all red with more
+    /// flags than mines.
+    fn flag_gauge(&self, flags: u16) -> Vec<Span<'static>> {
+        let mines = self.mine_count().max(1) as u32;
+        if flags as u32 > mines {
+            return vec![" ".into(), "█".repeat(FLAG_GAUGE_WIDTH).red()];
+        }
+        let filled = (flags as u32 * FLAG_GAUGE_WIDTH as u32 + mines / 2) / mines;
+        let filled = filled as usize;
+        vec![
+            " ".into(),
+            "█".repeat(filled).into(),
+            "░".repeat(FLAG_GAUGE_WIDTH - filled).dark_gray(),
+        ]
+    }
+
+    fn border_style(&self) -> Style {
+        let (bright, steady) = match self.game_state {
+            GameState::Won => (Color::LightGreen, Color::Green),
+            GameState::GameOver => (Color::LightRed, Color::Red),
+            GameState::Playing => return Style::default(),
+        };
+        match self.finished_at {
+            Some(finished_at) if finished_at.elapsed() < FLASH_DURATION => {
+                let pulse = finished_at.elapsed().as_millis() / FLASH_INTERVAL.as_millis();
+                if pulse.is_multiple_of(2) {
+                    Style::default().fg(bright)
+                } else {
+                    Style::default().fg(Color::DarkGray)
+                }
+            }
+            _ => Style::default().fg(steady),
+        }
+    }
+
+    /// Completes a won board with flags on the remaining mines instead of revealing them.
+    fn flag_all_mines(&mut self) {
+        self.invalidate_board();
+        for row in &mut self.board {
+            for field in &mut row.fields {
+                field.marked = field.is_mine;
+            }
+        }
+    }
+
+    fn reveal_all(&mut self) {
+        self.invalidate_board();
+        for row in &mut self.board {
+            for field in &mut row.fields {
+                field.revealed = true;
+            }
+        }
+    }
+
+    /// Fingerprint of the mine layout, so two players can confirm they are on the same
+    /// board. A 64 bit FNV-1a over the dimensions and mine positions, which unlike the
+    /// std hashers is stable across runs and builds. `None` until the mines are placed.
+    pub fn board_hash(&self) -> Option<u64> {
+        if !self.initialized {
+            return None;
+        }
+        let mut bytes = vec![self.rows, self.columns];
+        for (row_index, row) in self.board.iter().enumerate() {
+            for (column_index, field) in row.fields.iter().enumerate() {
+                if field.is_mine {
+                    bytes.extend([row_index as u8, column_index as u8]);
+                }
+            }
+        }
+        Some(bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+        }))
+    }
+
+    /// One line of `key=value` pairs describing a finished game, `None` while playing. The
+    /// keys are documented in the README and only ever added to.
+    pub fn result_line(&self) -> Option<String> {
+        let outcome = match self.outcome() {
+            Outcome::InProgress => return None,
+            Outcome::Won => "won",
+            Outcome::Lost => "lost",
+        };
+        let board = self
+            .board_hash()
+            .map_or("none".to_string(), |hash| format!("{hash:016x}"));
+        let seed = self
+            .seed
+            .map_or("none".to_string(), |seed| seed.seed.to_string());
+        Some(format!(
+            "outcome={outcome} columns={} rows={} mines={} time_ms={} clicks={} board={board} \
+             assisted={} seed={seed}",
+            self.columns,
+            self.rows,
+            self.mine_count(),
+            self.timer.elapsed().as_millis(),
+            self.clicks,
+            self.assisted
+        ))
+    }
+
+    /// Writes the board and moves of the game to `termsweeper/games` in the data directory
+    /// and tells where in the status line.
+    pub fn save_game(&mut self) {
+        let Some(hash) = self.board_hash() else {
+            return;
+        };
+        let Some(directory) = games_directory() else {
+            self.set_status(
+                "No data directory to save the game to",
+                Color::LightRed,
+                STATUS_DURATION,
+            );
+            return;
+        };
+        let seconds = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs());
+        let path = directory.join(format!("{hash:016x}-{seconds}.txt"));
+        let result = fs::create_dir_all(&directory).and_then(|_| fs::write(&path, self.export()));
+        match result {
+            Ok(()) => self.set_status(
+                format!("Saved to {}", path.display()),
+                Color::LightGreen,
+                STATUS_DURATION,
+            ),
+            Err(error) => self.set_status(
+                format!("Can't save the game: {error}"),
+                Color::LightRed,
+                STATUS_DURATION,
+            ),
         }
     }
 
-    fn toggle_mark(&mut self) -> bool {
-        if !self.get_field(self.cursor).revealed {
-            self.get_field_mut(self.cursor).marked = !self.get_field(self.cursor).marked;
-            true
-        } else {
-            false
-        }
+    /// Shows `message` in the status line for `duration`, or until the next input if that
+    /// comes first. Messages set without it stay until the next input.
+    pub fn set_status(&mut self, message: impl Into<String>, color: Color, duration: Duration) {
+        self.message = Some((message.into(), color));
+        self.message_until = Some(Instant::now() + duration);
     }
 
-    fn reveal(&mut self) -> bool {
-        if !self.initialized {
-            self.initialize();
+    /// The mines in the format [`Termsweeper::from_layout`] reads, one line per row.
+    pub fn layout(&self) -> String {
+        let mut text = String::new();
+        for row in &self.board {
+            for field in &row.fields {
+                text.push(if field.is_mine { '*' } else { '.' });
+            }
+            text.push('\n');
         }
-        if !self.get_field(self.cursor).marked && !self.get_field(self.cursor).revealed {
-            self.get_field_mut(self.cursor).revealed = true;
-            if self.get_field(self.cursor).is_mine {
-                self.game_state = GameState::GameOver;
-                self.reveal_all();
-            } else {
-                self.fields_left_to_reveal -= 1;
-                if self.get_field(self.cursor).adjacent_mines == 0 {
-                    let mut adjacent_fields = self.get_valid_adjacent_fields(self.cursor).to_vec();
-                    while let Some(location) = adjacent_fields.pop() {
-                        if !self.get_field(location).revealed {
-                            self.get_field_mut(location).revealed = true;
-                            self.fields_left_to_reveal -= 1;
-                            if self.get_field(location).adjacent_mines == 0 {
-                                adjacent_fields
-                                    .append(&mut self.get_valid_adjacent_fields(location).to_vec());
-                            }
-                        }
-                    }
-                }
-                if self.fields_left_to_reveal == 0 {
-                    self.game_state = GameState::Won;
-                    self.reveal_all();
-                }
+        text
+    }
+
+    /// Places the mines of boards generated from now on with `generator`.
+    pub fn set_generator(&mut self, generator: Rc<dyn BoardGenerator>) {
+        self.generator = Some(generator);
+    }
+
+    /// The seed of a finished game's board as `key=value` pairs, the arguments that make
+    /// `--find-board` generate the board again and the board hash to verify it. `None`
+    /// while playing, for boards that didn't come from a fresh seed and for hardcore boards,
+    /// whose mines weren't kept clear of the first reveal like the search keeps them.
+    pub fn seed_line(&self) -> Option<String> {
+        let BoardSeed { seed, first_reveal } = self
+            .seed
+            .filter(|_| self.is_finished() && self.first_click_safe)?;
+        Some(format!(
+            "seed={seed} columns={} rows={} mines={} first={},{} board={:016x}",
+            self.columns,
+            self.rows,
+            self.number_of_mines,
+            first_reveal.0 + 1,
+            first_reveal.1 + 1,
+            self.board_hash()?
+        ))
+    }
+
+    /// The game as text, as documented in the README: a header, the mine layout in the
+    /// format [`Termsweeper::from_layout`] reads, then the recorded moves and the result.
+    pub fn export(&self) -> String {
+        let mut text = String::from("termsweeper-game 1\n");
+        let _ = writeln!(
+            text,
+            "board {} {} {} {}",
+            self.columns, self.rows, self.number_of_mines, self.neighbor_radius
+        );
+        text += "layout\n";
+        text += &self.layout();
+        text += "moves\n";
+        if let Some(first) = self.history.front() {
+            for entry in &self.history {
+                let _ = writeln!(
+                    text,
+                    "{} {} {} {}",
+                    entry.at.duration_since(first.at).as_millis(),
+                    entry.action.label(),
+                    entry.location.0 + 1,
+                    entry.location.1 + 1
+                );
             }
-            true
-        } else {
-            false
         }
+        let result = match self.game_state {
+            GameState::Playing => "unfinished",
+            GameState::Won => "won",
+            GameState::GameOver => "lost",
+        };
+        let _ = writeln!(text, "result {result} {}", self.timer.elapsed().as_millis());
+        text
     }
 
-    fn reveal_all(&mut self) {
-        for row in &mut self.board {
-            for field in &mut row.fields {
-                field.revealed = true;
+    /// The game in progress as text for [`Termsweeper::from_autosave`], the layout of
+    /// [`Termsweeper::layout`] with `o` for revealed fields, `f`/`F` for flagged ones, `m`/`M`
+    /// for ones noted as uncertain, in capitals over mines, and `x` for exploded mines.
+    /// `None` until the mines are placed and once the game is over, leaving nothing to resume.
+    pub fn autosave(&self) -> Option<String> {
+        if !self.initialized || self.is_finished() {
+            return None;
+        }
+        let mut text = String::from("termsweeper-autosave 1\n");
+        let _ = writeln!(text, "radius {}", self.neighbor_radius);
+        let _ = writeln!(text, "self_inclusive {}", self.self_inclusive_counts as u8);
+        let _ = writeln!(text, "zen {}", self.zen as u8);
+        let _ = writeln!(text, "hardcore {}", self.hardcore as u8);
+        let _ = writeln!(text, "assisted {}", self.assisted as u8);
+        let _ = writeln!(text, "clicks {}", self.clicks);
+        let _ = writeln!(text, "lives {}", self.lives_left);
+        let _ = writeln!(text, "peeks {}", self.peeks_left);
+        let _ = writeln!(text, "time {}", self.timer.elapsed().as_millis());
+        text += "fields\n";
+        for row in &self.board {
+            for field in &row.fields {
+                text.push(match (field.is_mine, field) {
+                    (_, Field { exploded: true, .. }) => 'x',
+                    (_, Field { revealed: true, .. }) => 'o',
+                    (false, Field { marked: true, .. }) => 'f',
+                    (true, Field { marked: true, .. }) => 'F',
+                    (false, Field { maybe: true, .. }) => 'm',
+                    (true, Field { maybe: true, .. }) => 'M',
+                    (false, _) => '.',
+                    (true, _) => '*',
+                });
             }
+            text.push('\n');
         }
+        Some(text)
     }
 
     pub fn render_game_screen(&self, area: Rect, buf: &mut Buffer) {
         let top = match self.game_state {
             GameState::Won => Title::from(" Termsweeper - VICTORY ".yellow().bold()),
             GameState::GameOver => Title::from(" Termsweeper - GAME OVER ".red().bold()),
+            _ if self.paused => Title::from(" Termsweeper - PAUSED ".yellow().bold()),
+            _ if self.zen => Title::from(" Termsweeper - Zen ".green().bold()),
             _ => Title::from(" Termsweeper - Game ".green().bold()),
         };
         let mut navigation = match self.game_state {
@@ -377,59 +3099,1089 @@ impl Termsweeper {
                 "Right".into(),
                 "<L/→> ".green().bold(),
                 "Mark".into(),
-                "<M/Enter> ".green().bold(),
+                if self.enter_reveals {
+                    "<M> "
+                } else {
+                    "<M/Enter> "
+                }
+                .green()
+                .bold(),
+                "Maybe".into(),
+                "<U> ".green().bold(),
                 "Reveal".into(),
-                "<Space> ".green().bold(),
+                format!("<{}> ", self.reveal_keys()).green().bold(),
+                "Hint".into(),
+                "<I> ".green().bold(),
+                "Check".into(),
+                "<V> ".green().bold(),
+                "Go to".into(),
+                "<:> ".green().bold(),
+            ],
+            _ => vec![
+                " Retry".into(),
+                "<R> ".green().bold(),
+                "New game".into(),
+                "<N> ".green().bold(),
+                "Save".into(),
+                "<W> ".green().bold(),
             ],
-            _ => vec![" ".into()],
         };
+        if self.seed_line().is_some() {
+            navigation.append(&mut vec!["Copy seed".into(), "<C> ".green().bold()]);
+        }
         navigation.append(&mut vec![
             "Exit to menu".into(),
             "<E> ".green().bold(),
             "Quit".into(),
             "<Q> ".green().bold(),
         ]);
-        let bottom = Title::from(Line::from(navigation));
+        let bottom = if self.show_key_bar {
+            Title::from(Line::from(navigation)).alignment(Alignment::Center)
+        } else {
+            Title::from(self.status_line().0).alignment(Alignment::Left)
+        };
 
-        let outer_border = Block::default()
+        let mut outer_border = Block::default()
             .title(top.alignment(Alignment::Center))
+            .title(bottom.position(Position::Bottom))
+            .borders(Borders::ALL)
+            .border_set(border::THICK)
+            .border_style(self.border_style());
+        let flags = self.flag_count();
+        let mut header = if self.show_flag_count {
+            format!(
+                " {} of {} flagged ",
+                format_count(flags as i64),
+                format_count(self.mine_count() as i64)
+            )
+        } else {
+            format!(
+                " {} mines left ",
+                format_count(self.mine_count() as i64 - flags as i64)
+            )
+        };
+        if self.lives > 1 {
+            header += &format!("- {} ", self.lives_left_label());
+        }
+        if self.show_cursor_position {
+            header = format!(" ({},{}) -{header}", self.cursor.0 + 1, self.cursor.1 + 1);
+        }
+        if self.is_finished() {
+            header += &format!("- {} clicks ", format_count(self.clicks as i64));
+            if self.assisted {
+                header += "- assisted ";
+            }
+        }
+        if !self.zen {
+            header += &format!("- {} ", format_duration(self.timer.elapsed(), false));
+        }
+        let mut title = vec![];
+        if self.show_flag_gauge {
+            title = self.flag_gauge(flags);
+        }
+        title.push(header.into());
+        outer_border =
+            outer_border.title(Title::from(Line::from(title)).alignment(Alignment::Right));
+        if self.flag_assist && matches!(self.game_state, GameState::Playing) {
+            outer_border = outer_border.title(
+                Title::from(" FLAG ASSIST ".black().on_yellow().bold())
+                    .alignment(Alignment::Center),
+            );
+        }
+        if let Some(hash) = self.board_hash() {
+            let mut board = format!(" Board {hash:016x} ");
+            if let Some(seed) = self.seed.filter(|_| self.is_finished()) {
+                board += &format!("- seed {} ", seed.seed);
+            }
+            outer_border =
+                outer_border.title(Title::from(board.dark_gray()).alignment(Alignment::Left));
+        }
+        let inner_area = outer_border.inner(area);
+        outer_border.render(area, buf);
+        let status_height = if self.show_key_bar { 1 } else { 0 };
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(status_height)])
+            .split(inner_area);
+        let mut board_area = layout[0];
+        if self.show_history {
+            let [area, history_area] =
+                Layout::horizontal([Constraint::Min(0), Constraint::Length(HISTORY_WIDTH)])
+                    .areas(board_area);
+            board_area = area;
+            self.render_history(history_area, buf);
+        }
+        if self.obscured {
+            let [_, message_area, _] = Layout::vertical([
+                Constraint::Min(0),
+                Constraint::Length(1),
+                Constraint::Min(0),
+            ])
+            .areas(board_area);
+            Paragraph::new("Paused - press any key to continue")
+                .centered()
+                .render(message_area, buf);
+        } else {
+            self.render_playing_board(board_area, buf);
+        }
+        if self.show_key_bar {
+            self.render_status_line(layout[1], buf);
+        }
+    }
+
+    /// The latest moves that fit the panel, with the time since the first move.
+    fn render_history(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .title(Title::from(" History ").alignment(Alignment::Center))
             .title(
-                bottom
+                Title::from(Line::from(vec!["<PgUp/PgDn>".green().bold()]))
                     .alignment(Alignment::Center)
                     .position(Position::Bottom),
             )
-            .borders(Borders::ALL)
-            .border_set(border::THICK);
-        let inner_area = outer_border.inner(area);
-        outer_border.render(area, buf);
-        self.render_playing_board(inner_area, buf);
+            .borders(Borders::LEFT | Borders::TOP | Borders::BOTTOM)
+            .border_style(Style::new().dark_gray());
+        let inner_area = block.inner(area);
+        block.render(area, buf);
+        let Some(first) = self.history.front() else {
+            Paragraph::new("No moves yet")
+                .dark_gray()
+                .centered()
+                .render(inner_area, buf);
+            return;
+        };
+        let end = self.history.len() - self.history_scroll;
+        let start = end.saturating_sub(inner_area.height as usize);
+        let lines: Vec<Line> = self
+            .history
+            .range(start..end)
+            .map(|entry| {
+                let (row, column) = entry.location;
+                Line::from(vec![
+                    format!(
+                        " {:>6.1}s ",
+                        entry.at.duration_since(first.at).as_secs_f64()
+                    )
+                    .dark_gray(),
+                    format!("{:<7}", entry.action.label()).into(),
+                    format!("{},{}", row + 1, column + 1).bold(),
+                ])
+            })
+            .collect();
+        Paragraph::new(lines).render(inner_area, buf);
     }
 
-    fn render_playing_board(&self, area: Rect, buf: &mut Buffer) {
-        const ROW_SIZE: u16 = 2;
-        let rows = self.board.len();
-        let mut constraints = vec![Constraint::Min(0)];
-        constraints.append(&mut Constraint::from_maxes(vec![ROW_SIZE; rows - 1]));
-        constraints.push(Constraint::Max(ROW_SIZE - 1));
-        constraints.push(Constraint::Min(0));
-        let layout = Layout::default()
-            .direction(ratatui::layout::Direction::Vertical)
-            .constraints(constraints)
-            .split(area);
-        let mut i = 1;
-        for row in &self.board {
-            let row_border = if i == self.board.len() {
-                Borders::NONE
+    fn render_status_line(&self, area: Rect, buf: &mut Buffer) {
+        let (status, keys) = self.status_line();
+        Paragraph::new(status).render(area, buf);
+        Paragraph::new(keys)
+            .alignment(Alignment::Right)
+            .render(area, buf);
+    }
+
+    /// The status line below the board as its text and the keys shown on the right.
+    fn status_line(&self) -> (Line<'_>, Line<'_>) {
+        if let Some(input) = &self.command_input {
+            let (row, column) = self.cursor_position();
+            let status = Line::from(vec![
+                format!(" Go to <row,column> (at {},{}): ", row + 1, column + 1)
+                    .green()
+                    .bold(),
+                input.as_str().into(),
+                "_".slow_blink(),
+            ]);
+            (status, Line::default())
+        } else if let Some((message, color)) = self.message.as_ref().filter(|_| {
+            self.message_until
+                .is_none_or(|until| until > Instant::now())
+        }) {
+            (format!(" {message}").fg(*color).into(), Line::default())
+        } else if let Some(anchor) = self.box_anchor {
+            let status = Line::from(vec![
+                format!(
+                    " Box {}×{} from ({},{}) - ",
+                    anchor.0.abs_diff(self.cursor.0) + 1,
+                    anchor.1.abs_diff(self.cursor.1) + 1,
+                    anchor.0 + 1,
+                    anchor.1 + 1
+                )
+                .into(),
+                "Select covered fields".into(),
+                "<B> ".green().bold(),
+                "Cancel".into(),
+                "<Esc>".green().bold(),
+            ]);
+            (status, Line::default())
+        } else if !self.selection.is_empty() {
+            let status = Line::from(vec![
+                format!(" {} selected - ", self.selection.len()).into(),
+                "Mark all".into(),
+                "<Shift+M> ".green().bold(),
+                "Reveal all".into(),
+                "<Shift+R> ".green().bold(),
+                "Clear".into(),
+                "<Esc>".green().bold(),
+            ]);
+            (status, Line::default())
+        } else if self.initialized && matches!(self.game_state, GameState::Playing) {
+            let forced_moves =
+                solver::forced_deductions(&self.player_view(), self.neighbor_radius).count();
+            let mut status = vec![
+                " Forced moves: ".dark_gray(),
+                forced_moves.to_string().bold(),
+            ];
+            if forced_moves == 0 {
+                status.push(" - guess required".yellow());
+            }
+            let mut keys = vec![];
+            if self.peeks_left > 0 {
+                keys.push(format!("Peek ({} left)", self.peeks_left).into());
+                keys.push("<P> ".green().bold());
+            }
+            keys.append(&mut vec![
+                "Flag assist".into(),
+                "<Shift+V> ".green().bold(),
+                "Select".into(),
+                "<X> ".green().bold(),
+                "Box".into(),
+                "<B> ".green().bold(),
+                "History".into(),
+                "<Shift+H> ".green().bold(),
+            ]);
+            (Line::from(status), Line::from(keys))
+        } else if let Some(analysis) = self
+            .loss_analysis
+            .as_ref()
+            .filter(|_| matches!(self.game_state, GameState::GameOver))
+        {
+            let color = if analysis.safe.is_some() {
+                Color::LightGreen
             } else {
-                Borders::BOTTOM
+                Color::Yellow
             };
-            let cursor_location = if i - 1 == self.cursor.0.into() {
-                Some(self.cursor.1)
+            (
+                format!(" {}", analysis.explanation).fg(color).into(),
+                Line::default(),
+            )
+        } else if self.initialized {
+            (
+                format!(" {}", self.flag_summary()).dark_gray().into(),
+                Line::from(vec!["History".into(), "<Shift+H> ".green().bold()]),
+            )
+        } else {
+            (Line::default(), Line::default())
+        }
+    }
+
+    /// [`Termsweeper::player_view`] with flags taken for covered fields, so deductions
+    /// rest on the numbers alone.
+    fn numbers_view(&self) -> Vec<Vec<solver::Cell>> {
+        self.player_view()
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|cell| match cell {
+                        solver::Cell::Flagged => solver::Cell::Hidden,
+                        cell => cell,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The board as the player sees it, for the solver.
+    fn player_view(&self) -> Vec<Vec<solver::Cell>> {
+        self.board
+            .iter()
+            .map(|row| {
+                row.fields
+                    .iter()
+                    .map(|field| {
+                        if field.revealed {
+                            solver::Cell::Revealed(field.adjacent_mines)
+                        } else if field.marked {
+                            solver::Cell::Flagged
+                        } else {
+                            solver::Cell::Hidden
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Width the game screen needs to show the board with the selected symbols.
+    pub fn required_width(&self) -> u16 {
+        let history_width = if self.show_history { HISTORY_WIDTH } else { 0 };
+        self.board_width(&self.render_options()) + history_width + 2
+    }
+
+    fn board_width(&self, options: &RenderOptions) -> u16 {
+        self.columns as u16 * options.field_size() - 1
+    }
+
+    fn render_options(&self) -> RenderOptions {
+        RenderOptions {
+            symbols: self.symbols,
+            numbers: self.numbers.clone(),
+            number_width: self.number_width(),
+            theme: self.theme,
+            checkerboard: self.checkerboard,
+            x_ray: self.x_ray,
+            flag_assist: self.flag_assist,
+            dim_satisfied: self.dim_satisfied,
+            count_flags: self.count_adjacent_flags,
+            square_fields: self.square_fields,
+            jumbo_fields: self.jumbo_fields,
+            blink_mines: self.blink_mines_on_loss && matches!(self.game_state, GameState::GameOver),
+            dot_empty_fields: self.dot_empty_fields,
+        }
+    }
+
+    /// Digits of the largest number the neighbourhood allows.
+    fn number_width(&self) -> u16 {
+        let side = 2 * self.neighbor_radius as u16 + 1;
+        (side * side - 1).to_string().len() as u16
+    }
+
+    /// Height the game screen needs to show the board and the status line.
+    pub fn required_height(&self) -> u16 {
+        self.board_height(&self.render_options()) + self.frame_height()
+    }
+
+    /// Rows of the game screen besides the board: the border, and the status line unless
+    /// it is on the border with the key bar hidden.
+    fn frame_height(&self) -> u16 {
+        if self.show_key_bar {
+            3
+        } else {
+            2
+        }
+    }
+
+    fn board_height(&self, options: &RenderOptions) -> u16 {
+        self.rows as u16 * options.row_size() - 1
+    }
+
+    pub fn render_playing_board(&self, area: Rect, buf: &mut Buffer) {
+        let mut options = self.render_options();
+        if self.board_width(&options) > area.width || self.board_height(&options) > area.height {
+            options.jumbo_fields = false;
+        }
+        if self.board_width(&options) > area.width {
+            options.symbols = SymbolSet::Ascii;
+            options.numbers = NumberGlyphs::default();
+        }
+        if self.board_width(&options) > area.width {
+            options.square_fields = false;
+        }
+        let flood_wave = self
+            .flood_animation
+            .as_ref()
+            .map(|animation| (animation.started, animation.wave()));
+        let mut cache = self.board_cache.borrow_mut();
+        let cache = &mut *cache;
+        let locations: Vec<(u8, u8)> = match &cache.frame {
+            Some(frame) if frame.area == area && frame.options == options => {
+                let mut locations = std::mem::take(&mut cache.dirty);
+                // A flag changes whether the numbers around it are satisfied.
+                if self.dim_satisfied || self.count_adjacent_flags {
+                    let around: Vec<_> = locations
+                        .iter()
+                        .flat_map(|location| self.get_valid_adjacent_fields(*location))
+                        .collect();
+                    locations.extend(around);
+                }
+                // Any change can shift the shading of fields around it.
+                if self.danger_shading && !locations.is_empty() {
+                    locations.extend(
+                        (0..self.rows)
+                            .flat_map(|row| (0..self.columns).map(move |column| (row, column))),
+                    );
+                }
+                locations.insert(frame.cursor);
+                locations.insert(self.cursor);
+                locations.extend(self.peek.map(|(location, _)| location));
+                if let (Some(animation), Some((_, wave))) = (&self.flood_animation, flood_wave) {
+                    let drawn = match frame.flood_wave {
+                        Some((started, drawn)) if started == animation.started => drawn,
+                        _ => 0,
+                    };
+                    locations.extend(
+                        animation
+                            .waves
+                            .iter()
+                            .filter(|(_, ring)| (drawn + 1..=wave).contains(*ring))
+                            .map(|(location, _)| *location),
+                    );
+                }
+                locations.into_iter().collect()
+            }
+            _ => {
+                cache.dirty.clear();
+                cache.frame = Some(CachedFrame {
+                    area,
+                    options: options.clone(),
+                    buffer: Buffer::empty(area),
+                    fields: self.field_layout(area, &options),
+                    cursor: self.cursor,
+                    flood_wave: None,
+                });
+                (0..self.rows)
+                    .flat_map(|row| (0..self.columns).map(move |column| (row, column)))
+                    .collect()
+            }
+        };
+        let Some(frame) = &mut cache.frame else {
+            return;
+        };
+        let box_fields: BTreeSet<_> = self.box_fields().into_iter().collect();
+        let danger = if self.danger_shading {
+            self.danger_scores()
+        } else {
+            BTreeMap::new()
+        };
+        for location in locations {
+            let (field_area, borders) = frame.fields[location.0 as usize][location.1 as usize];
+            for y in field_area.top()..field_area.bottom() {
+                for x in field_area.left()..field_area.right() {
+                    frame.buffer.get_mut(x, y).reset();
+                }
+            }
+            let highlight = if location == self.cursor {
+                match self.cursor_style {
+                    CursorStyle::Background => Some(self.cursor_color()),
+                    CursorStyle::Brackets => None,
+                }
+            } else if self
+                .loss_analysis
+                .as_ref()
+                .is_some_and(|analysis| analysis.safe == Some(location))
+            {
+                Some(Color::Green)
+            } else if self.selection.contains(&location) {
+                Some(Color::DarkGray)
+            } else if box_fields.contains(&location) {
+                Some(Color::Magenta)
             } else {
-                None
+                danger
+                    .get(&location)
+                    .map(|score| Color::Rgb(40 + (score * 150.0) as u8, 0, 0))
+            };
+            let peeked = self
+                .peek
+                .is_some_and(|(peeked, at)| peeked == location && at.elapsed() < PEEK_DURATION);
+            let mut field = self.get_field(location).clone();
+            if let (Some(animation), Some((_, wave))) = (&self.flood_animation, flood_wave) {
+                if animation
+                    .waves
+                    .get(&location)
+                    .is_some_and(|ring| *ring > wave)
+                {
+                    field.revealed = false;
+                }
+            }
+            let field_frame = FieldFrame {
+                location,
+                area: field_area,
+                borders,
+                highlight,
+                peeked,
+                satisfied: options.dim_satisfied && self.is_satisfied(location),
+                adjacent_flags: if options.count_flags {
+                    self.adjacent_flags(location)
+                } else {
+                    0
+                },
+            };
+            self.cell_renderer
+                .render(&field, &field_frame, &mut frame.buffer, &options);
+        }
+        frame.cursor = self.cursor;
+        frame.flood_wave = flood_wave;
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                *buf.get_mut(x, y) = frame.buffer.get(x, y).clone();
+            }
+        }
+        if self.cursor_style == CursorStyle::Brackets {
+            let (field_area, borders) =
+                frame.fields[self.cursor.0 as usize][self.cursor.1 as usize];
+            self.render_cursor_brackets(field_area, borders, buf);
+        }
+        if let Some(started) = self.celebration {
+            render_confetti(started.elapsed(), &frame.fields, buf);
+        }
+    }
+
+    /// Area and borders of every field, indexed like the board. Every field but the last of
+    /// a row and column carries the separator to its neighbour.
+    fn field_layout(&self, area: Rect, options: &RenderOptions) -> Vec<Vec<(Rect, Borders)>> {
+        let row_areas = split_cells(
+            area,
+            Direction::Vertical,
+            options.row_size(),
+            self.board.len(),
+        );
+        (0..self.board.len())
+            .map(|row_index| {
+                let fields = self.board[row_index].fields.len();
+                let field_areas = split_cells(
+                    row_areas[row_index],
+                    Direction::Horizontal,
+                    options.field_size(),
+                    fields,
+                );
+                (0..fields)
+                    .map(|column_index| {
+                        let borders =
+                            grid_borders((row_index, column_index), (self.board.len(), fields));
+                        (field_areas[column_index], borders)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Separators a field draws to its right and below. Every field draws the ones it shares
+/// with a neighbour, so the grid has the same lines between all fields and none along the
+/// board's edge, which the outer border frames instead.
+fn grid_borders(location: (usize, usize), size: (usize, usize)) -> Borders {
+    let mut borders = Borders::NONE;
+    if location.1 + 1 < size.1 {
+        borders |= Borders::RIGHT;
+    }
+    if location.0 + 1 < size.0 {
+        borders |= Borders::BOTTOM;
+    }
+    borders
+}
+
+/// Splits `area` into `count` centered cells of `size`, the last one without room for a
+/// separator.
+fn split_cells(area: Rect, direction: Direction, size: u16, count: usize) -> Rc<[Rect]> {
+    let mut constraints = vec![Constraint::Min(0)];
+    constraints.append(&mut Constraint::from_maxes(vec![size; count - 1]));
+    constraints.push(Constraint::Max(size - 1));
+    constraints.push(Constraint::Min(0));
+    let layout = Layout::default()
+        .direction(direction)
+        .constraints(constraints)
+        .split(area);
+    layout[1..=count].into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+
+    /// Width the rendering tests use, room for the titles of the header side by side.
+    const SCREEN_WIDTH: u16 = 100;
+
+    /// Board with the mines of `layout` and the default settings.
+    fn board(layout: &str) -> Termsweeper {
+        Termsweeper::from_layout(layout, &Settings::default()).unwrap()
+    }
+
+    /// The game screen rendered into a [`TestBackend`] just tall enough for the board.
+    fn render(game: &Termsweeper) -> Buffer {
+        let backend = TestBackend::new(SCREEN_WIDTH, game.required_height());
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|frame| game.render_game_screen(frame.size(), frame.buffer_mut()))
+            .unwrap();
+        terminal.backend().buffer().clone()
+    }
+
+    /// The symbols on row `y` of `buffer`.
+    fn line(buffer: &Buffer, y: u16) -> String {
+        (buffer.area.left()..buffer.area.right())
+            .map(|x| buffer.get(x, y).symbol())
+            .collect()
+    }
+
+    /// The board as rendered, its fields and grid lines without the frame around them.
+    fn board_lines(buffer: &Buffer) -> Vec<String> {
+        (1..buffer.area.height - 2)
+            .map(|y| line(buffer, y).trim_matches(['┃', ' ']).to_string())
+            .collect()
+    }
+
+    /// Where `symbol` is drawn on the board in `buffer`, row by row.
+    fn find(buffer: &Buffer, symbol: &str) -> Vec<(u16, u16)> {
+        let area = buffer.area;
+        (1..area.bottom() - 2)
+            .flat_map(|y| (area.left()..area.right()).map(move |x| (x, y)))
+            .filter(|&(x, y)| buffer.get(x, y).symbol() == symbol)
+            .collect()
+    }
+
+    #[test]
+    fn renders_the_board_grid() {
+        let mut game = board("*...\n....\n...*\n");
+        game.reveal_at((0, 3));
+        assert_eq!(
+            board_lines(&render(&game)),
+            ["?│1│ │", "─┼─┼─┼─", "?│1│1│1", "─┼─┼─┼─", "?│?│?│?"]
+        );
+    }
+
+    #[test]
+    fn renders_the_grid_between_fields_only() {
+        let mut game = board("*....\n.....\n...*.\n.....\n");
+        game.mark_at((0, 0));
+        game.reveal_at((0, 4));
+        game.reveal_at((3, 4));
+        game.mark_at((3, 0));
+        let buffer = render(&game);
+        // The board with a column of the frame's inside on either side, where separators
+        // along the board edge would show.
+        let snapshot: Vec<String> = (1..8)
+            .map(|y| line(&buffer, y).chars().skip(45).take(11).collect())
+            .collect();
+        assert_eq!(
+            snapshot,
+            [
+                " X│1│ │ │  ",
+                " ─┼─┼─┼─┼─ ",
+                " ?│1│1│1│1 ",
+                " ─┼─┼─┼─┼─ ",
+                " ?│?│?│?│? ",
+                " ─┼─┼─┼─┼─ ",
+                " X│?│?│?│1 ",
+            ]
+        );
+    }
+
+    #[test]
+    fn shades_every_other_field_by_its_location() {
+        let mut game = board("*...\n....\n...*\n");
+        game.checkerboard = true;
+        let buffer = render(&game);
+        let fields = find(&buffer, "?");
+        assert_eq!(fields.len(), 12);
+        for (index, (x, y)) in fields.into_iter().enumerate() {
+            let location = ((index / 4) as u8, (index % 4) as u8);
+            if location == game.cursor_position() {
+                continue;
+            }
+            let shaded = buffer.get(x, y).bg == CHECKERBOARD_SHADE;
+            assert_eq!(shaded, (location.0 + location.1) % 2 == 1, "{location:?}");
+        }
+    }
+
+    #[test]
+    fn colors_numbers_by_the_theme() {
+        let mut game = board("*...\n....\n...*\n");
+        game.reveal_at((0, 3));
+        let buffer = render(&game);
+        let ones = find(&buffer, "1");
+        assert_eq!(ones.len(), 4);
+        for (x, y) in ones {
+            assert_eq!(buffer.get(x, y).fg, game.theme.numbers[0]);
+        }
+    }
+
+    #[test]
+    fn new_lengthens_sides_below_the_minimum() {
+        let game = Termsweeper::new(0, 1, 0);
+        assert_eq!(
+            (game.columns, game.rows, game.number_of_mines),
+            (MIN_SIDE, MIN_SIDE, 1)
+        );
+    }
+
+    #[test]
+    fn new_keeps_one_field_free_of_mines() {
+        let game = Termsweeper::new(2, 2, 9999);
+        assert_eq!((game.columns, game.rows, game.number_of_mines), (2, 2, 3));
+    }
+
+    #[test]
+    fn first_click_safe_boards_get_at_least_one_mine() {
+        // The neighbourhood of the middle field covers these boards entirely.
+        for (side, neighbor_radius) in [(MIN_SIDE, 1), (3, 1), (5, 2)] {
+            let settings = Settings {
+                columns: side,
+                rows: side,
+                number_of_mines: 10,
+                neighbor_radius,
+                ..Settings::default()
+            };
+            let mut game = Termsweeper::from_settings(&settings);
+            assert_eq!(game.mine_count(), 1);
+            game.set_cursor(side / 2, side / 2);
+            game.initialize();
+            assert_eq!(game.dimensions(), (side, side, 1));
+            assert_eq!(game.validate(), Ok(()));
+            assert!(game.reveal_at((side / 2, side / 2)));
+            assert!(!game.is_finished());
+        }
+    }
+
+    #[test]
+    fn from_layout_rejects_boards_below_the_minimum() {
+        let settings = Settings::default();
+        assert!(Termsweeper::from_layout("*..", &settings).is_err());
+        assert!(Termsweeper::from_layout("*\n.\n.\n", &settings).is_err());
+        assert!(Termsweeper::from_layout("*.\n..\n", &settings).is_ok());
+    }
+
+    #[test]
+    fn placed_boards_hold_their_invariants_across_seeds() {
+        // Columns, rows, mines, neighbourhood radius and first-click safety: standard,
+        // nearly full, radius 2 and hardcore boards.
+        let boards = [
+            (9, 9, 10, 1, true),
+            (30, 16, 99, 1, true),
+            (8, 8, 55, 1, true),
+            (12, 10, 40, 2, true),
+            (5, 5, 24, 2, true),
+            (10, 10, 99, 1, false),
+        ];
+        for (columns, rows, number_of_mines, neighbor_radius, first_click_safe) in boards {
+            let settings = Settings {
+                columns,
+                rows,
+                number_of_mines,
+                neighbor_radius,
+                ..Settings::default()
+            };
+            for seed in 0..100 {
+                let mut game = Termsweeper::from_settings(&settings);
+                game.first_click_safe = first_click_safe;
+                game.set_generator(Rc::new(SeededGenerator { seed }));
+                game.set_cursor((seed % rows as u64) as u8, (seed % columns as u64) as u8);
+                game.initialize();
+                assert_eq!(game.validate(), Ok(()), "seed {seed} on {columns}×{rows}");
+            }
+        }
+    }
+
+    #[test]
+    fn validate_reports_a_wrong_number() {
+        let mut game = board("*...\n....\n...*\n");
+        game.get_field_mut((1, 1)).adjacent_mines = 3;
+        assert_eq!(
+            game.validate(),
+            Err("(2,2) shows 3 instead of 1 mines".to_string())
+        );
+    }
+
+    /// Two areas of zeros, top left and bottom right, that only touch diagonally at (2,2)
+    /// and (3,3).
+    const DIAGONAL_ZEROS: &str = "...*\n....\n....\n*...\n";
+
+    #[test]
+    fn flood_fill_follows_zeros_touching_diagonally() {
+        let mut game = board(DIAGONAL_ZEROS);
+        game.reveal_at((0, 0));
+        assert!(game.is_revealed((3, 3)));
+        assert!(game.is_won());
+    }
+
+    #[test]
+    fn flood_fill_of_zeros_only_follows_diagonals_too() {
+        let mut game = board(DIAGONAL_ZEROS);
+        game.flood_fill.reveal_borders = false;
+        game.reveal_at((0, 0));
+        assert!(game.is_revealed((3, 3)));
+        assert!(!game.is_revealed((1, 2)));
+    }
+
+    #[test]
+    fn flag_on_a_safe_field_only_holds_the_win_back() {
+        let mut game = board("*.*.\n....\n*.*.\n....\n");
+        assert!(game.mark_at((3, 3)));
+        for row in 0..4 {
+            for column in 0..4 {
+                let mine = row % 2 == 0 && column % 2 == 0;
+                if (row, column) != (3, 3) && !mine {
+                    assert!(game.reveal_at((row, column)));
+                }
+            }
+        }
+        assert!(!game.is_finished());
+        assert!(game.mark_at((3, 3)));
+        assert!(game.reveal_at((3, 3)));
+        assert!(game.is_won());
+    }
+
+    /// `text` from [`Termsweeper::export`] without the times, which differ between plays.
+    fn without_times(text: &str) -> Vec<String> {
+        text.lines()
+            .map(|line| match line.split_once(' ') {
+                Some((millis, rest)) if millis.parse::<u128>().is_ok() => rest.to_string(),
+                _ if line.starts_with("result ") => line.rsplit_once(' ').unwrap().0.to_string(),
+                _ => line.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn saved_games_read_back() {
+        let mut game = board("*...\n....\n..*.\n....\n");
+        game.reveal_at((0, 1));
+        game.mark_at((2, 2));
+        game.mark_at((0, 0));
+        game.mark_at((0, 0));
+        game.reveal_at((3, 0));
+        for location in [(0, 3), (2, 3), (3, 2), (3, 3)] {
+            game.reveal_at(location);
+        }
+        assert!(game.is_won());
+        let text = game.export();
+
+        let mut lines = text.lines();
+        assert_eq!(lines.next(), Some("termsweeper-game 1"));
+        assert_eq!(lines.next(), Some("board 4 4 2 1"));
+        assert_eq!(lines.next(), Some("layout"));
+        let layout: String = lines
+            .by_ref()
+            .take_while(|line| *line != "moves")
+            .map(|line| format!("{line}\n"))
+            .collect();
+        let mut replayed = board(&layout);
+        assert_eq!(replayed.layout(), game.layout());
+        for line in lines
+            .by_ref()
+            .take_while(|line| !line.starts_with("result "))
+        {
+            let fields: Vec<&str> = line.split(' ').collect();
+            let location = (
+                fields[2].parse::<u8>().unwrap() - 1,
+                fields[3].parse::<u8>().unwrap() - 1,
+            );
+            let acted = match fields[1] {
+                "reveal" => replayed.reveal_at(location),
+                "flag" | "unflag" => replayed.mark_at(location),
+                action => panic!("unknown move {action}"),
             };
-            row.render(layout[i], buf, row_border, cursor_location);
-            i += 1;
+            assert!(acted, "{line} changed nothing");
+        }
+        assert!(replayed.is_won());
+        assert_eq!(without_times(&replayed.export()), without_times(&text));
+    }
+
+    /// Zeros in the top rows above numbers, with a gap in a row of mines that only the
+    /// numbers prove safe.
+    const WALLED: &str = ".....\n.....\n**.**\n.....\n";
+
+    /// [`WALLED`] after revealing its top left corner under `policy`, with a flag on
+    /// `flagged` first.
+    fn flooded(policy: FloodFillPolicy, flagged: Option<(u8, u8)>) -> Termsweeper {
+        let mut game = board(WALLED);
+        game.flood_fill = policy;
+        if let Some(location) = flagged {
+            game.mark_at(location);
+        }
+        game.reveal_at((0, 0));
+        game
+    }
+
+    #[test]
+    fn flood_fill_reveals_the_numbered_border() {
+        let game = flooded(FloodFillPolicy::default(), None);
+        assert!(game.is_revealed((0, 4)));
+        assert!(game.is_revealed((1, 2)));
+        assert!(!game.is_revealed((2, 2)));
+    }
+
+    #[test]
+    fn flood_fill_of_zeros_only_leaves_the_border_covered() {
+        let policy = FloodFillPolicy {
+            reveal_borders: false,
+            ..FloodFillPolicy::default()
+        };
+        let game = flooded(policy, None);
+        assert!(game.is_revealed((0, 4)));
+        assert!(!game.is_revealed((1, 2)));
+    }
+
+    #[test]
+    fn flood_fill_through_numbers_opens_fields_they_prove_safe() {
+        let policy = FloodFillPolicy {
+            through_numbers: true,
+            ..FloodFillPolicy::default()
+        };
+        let game = flooded(policy, None);
+        assert!(game.is_revealed((2, 2)));
+        assert!(game.is_won());
+    }
+
+    #[test]
+    fn flood_fill_respects_flags_only_when_asked() {
+        let game = flooded(FloodFillPolicy::default(), Some((0, 4)));
+        assert!(game.is_revealed((0, 4)));
+        let policy = FloodFillPolicy {
+            respect_flags: true,
+            ..FloodFillPolicy::default()
+        };
+        let game = flooded(policy, Some((0, 4)));
+        assert!(!game.is_revealed((0, 4)));
+        assert!(game.is_flagged((0, 4)));
+        assert!(game.is_revealed((0, 3)));
+    }
+
+    /// The counts of every safe field of `game`, revealing them all, `-` for mines.
+    fn counts(game: &mut Termsweeper, mines: &[(u8, u8)]) -> Vec<String> {
+        let (columns, rows, _) = game.dimensions();
+        (0..rows)
+            .map(|row| {
+                (0..columns)
+                    .map(|column| {
+                        if mines.contains(&(row, column)) {
+                            return "-".to_string();
+                        }
+                        game.reveal_at((row, column));
+                        game.snapshot()[row as usize][column as usize].clone()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn counts_mines_two_fields_away_with_radius_2() {
+        let layout = "*....\n.....\n.....\n.....\n....*\n";
+        let settings = Settings {
+            neighbor_radius: 2,
+            ..Settings::default()
+        };
+        let mut game = Termsweeper::from_layout(layout, &settings).unwrap();
+        assert_eq!(
+            counts(&mut game, &[(0, 0), (4, 4)]),
+            ["-1100", "11100", "11211", "00111", "0011-"]
+        );
+        assert!(game.is_won());
+        assert_eq!(
+            counts(&mut board(layout), &[(0, 0), (4, 4)]),
+            ["-1000", "11000", "00000", "00011", "0001-"]
+        );
+    }
+
+    /// Asserts that movement, reveal and mark keys change nothing on the finished `game`.
+    fn assert_ignores_input(mut game: Termsweeper) {
+        // Skip the animations, which take the first key.
+        game.flood_animation = None;
+        game.celebration = None;
+        let cursor = game.cursor_position();
+        let snapshot = game.snapshot();
+        for code in [
+            KeyCode::Right,
+            KeyCode::Down,
+            KeyCode::Char('l'),
+            KeyCode::Char(' '),
+            KeyCode::Char('m'),
+        ] {
+            assert!(!game.handle_event(KeyEvent::new(code, KeyModifiers::NONE)));
+        }
+        assert_eq!(game.cursor_position(), cursor);
+        assert_eq!(game.snapshot(), snapshot);
+        assert!(!game.reveal_at((1, 1)));
+        assert!(!game.mark_at((1, 1)));
+        assert_eq!(game.snapshot(), snapshot);
+    }
+
+    #[test]
+    fn won_games_ignore_input() {
+        let mut game = board("*...\n....\n....\n");
+        game.reveal_at((2, 3));
+        assert!(game.is_won());
+        assert_ignores_input(game);
+    }
+
+    #[test]
+    fn lost_games_ignore_input() {
+        let mut game = board("*...\n....\n....\n");
+        game.reveal_at((0, 0));
+        assert_eq!(game.outcome(), Outcome::Lost);
+        assert_ignores_input(game);
+    }
+
+    #[test]
+    fn counts_leave_out_the_field_itself() {
+        let game = board("**..\n*.*.\n....\n");
+        let counts: Vec<Vec<u8>> = game
+            .board
+            .iter()
+            .map(|row| {
+                row.fields
+                    .iter()
+                    .map(|field| field.adjacent_mines)
+                    .collect()
+            })
+            .collect();
+        assert_eq!(counts, [[2, 3, 2, 1], [2, 4, 1, 1], [1, 2, 1, 1]]);
+    }
+
+    #[test]
+    fn ignores_reveals_and_flags_off_the_board() {
+        let mut game = board("*...\n....\n...*\n");
+        for location in [(3, 0), (0, 4), (255, 255)] {
+            assert!(!game.reveal_at(location));
+            assert!(!game.mark_at(location));
+        }
+        assert_eq!(game.cursor_position(), (0, 0));
+        assert!(!game.is_started());
+    }
+
+    #[test]
+    fn deletes_files_and_directories_by_renaming() {
+        let root = std::env::temp_dir().join(format!("termsweeper-{}", std::process::id()));
+        let games = root.join("games");
+        fs::create_dir_all(&games).unwrap();
+        fs::write(games.join("game.txt"), "").unwrap();
+        let stats = root.join("stats.toml");
+        fs::write(&stats, "").unwrap();
+        // Left over from an interrupted deletion.
+        fs::write(root.join("config.deleted"), "").unwrap();
+        for path in [games, stats, root.join("config.toml")] {
+            assert!(delete_by_renaming(&path).is_ok());
+            assert!(!path.exists());
+            assert!(!path.with_extension("deleted").exists());
         }
+        fs::remove_dir(root).unwrap();
+    }
+
+    #[test]
+    fn self_inclusive_counts_add_the_mine_itself() {
+        let settings = Settings {
+            self_inclusive_counts: true,
+            ..Settings::default()
+        };
+        let game = Termsweeper::from_layout("**..\n*.*.\n....\n", &settings).unwrap();
+        let counts: Vec<Vec<u8>> = game
+            .board
+            .iter()
+            .map(|row| {
+                row.fields
+                    .iter()
+                    .map(|field| field.adjacent_mines)
+                    .collect()
+            })
+            .collect();
+        assert_eq!(counts, [[3, 4, 2, 1], [3, 4, 2, 1], [1, 2, 1, 1]]);
+        assert_eq!(game.validate(), Ok(()));
+    }
+
+    #[test]
+    fn self_inclusive_counts_open_the_same_zeros() {
+        let settings = Settings {
+            self_inclusive_counts: true,
+            ..Settings::default()
+        };
+        let mut game = Termsweeper::from_layout(DIAGONAL_ZEROS, &settings).unwrap();
+        game.reveal_at((0, 0));
+        assert!(game.is_won());
+    }
+
+    #[test]
+    fn titles_the_game_by_its_state() {
+        let mut game = board("*...\n....\n...*\n");
+        assert!(line(&render(&game), 0).contains("Termsweeper - Game"));
+        game.reveal_at((0, 0));
+        assert!(line(&render(&game), 0).contains("Termsweeper - GAME OVER"));
     }
 }
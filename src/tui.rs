@@ -1,21 +1,56 @@
-use std::io::{self, stdout, Stdout};
+use std::{
+    io::{self, stdout, Stdout},
+    panic,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
-use crossterm::{execute, terminal::*};
+use crossterm::{
+    cursor,
+    event::{KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags},
+    execute,
+    terminal::*,
+};
 use ratatui::prelude::*;
 
 /// A type alias for the terminal type used in this application
 pub type Tui = Terminal<CrosstermBackend<Stdout>>;
 
+/// Whether the terminal was asked to report key repeats and releases, to undo it on restore.
+static EVENT_TYPES_REPORTED: AtomicBool = AtomicBool::new(false);
+
 /// Initialize the terminal
 pub fn init() -> io::Result<Tui> {
+    install_panic_hook();
     execute!(stdout(), EnterAlternateScreen)?;
     enable_raw_mode()?;
+    // Without this held keys arrive as repeated presses, which can't be told apart from
+    // pressing a key again.
+    if matches!(supports_keyboard_enhancement(), Ok(true)) {
+        execute!(
+            stdout(),
+            PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::REPORT_EVENT_TYPES)
+        )?;
+        EVENT_TYPES_REPORTED.store(true, Ordering::Relaxed);
+    }
     Terminal::new(CrosstermBackend::new(stdout()))
 }
 
 /// Restore the terminal to its original state
 pub fn restore() -> io::Result<()> {
-    execute!(stdout(), LeaveAlternateScreen)?;
+    if EVENT_TYPES_REPORTED.swap(false, Ordering::Relaxed) {
+        execute!(stdout(), PopKeyboardEnhancementFlags)?;
+    }
+    execute!(stdout(), LeaveAlternateScreen, cursor::Show)?;
     disable_raw_mode()?;
     Ok(())
 }
+
+/// Restore the terminal before a panic message is printed, otherwise it would be lost on
+/// the alternate screen and leave the terminal in raw mode
+fn install_panic_hook() {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let _ = restore();
+        hook(info);
+    }));
+}
@@ -0,0 +1,137 @@
+use crate::{
+    settings::Settings,
+    termsweeper::{FloodFillPolicy, Outcome, Termsweeper},
+};
+use crossterm::event::KeyEvent;
+use ratatui::{
+    prelude::*,
+    symbols::border,
+    widgets::{block::*, *},
+};
+
+/// The hand-made tutorial board, in the format of [`Termsweeper::from_layout`]. Revealing
+/// the top left corner opens most of it, the rest can be solved without guessing.
+const LAYOUT: &str = "\
+.......*
+........
+*.......
+......*.
+...*....";
+
+/// A lesson of the tutorial: what to do, and when it is done.
+struct Step {
+    prompt: &'static str,
+    done: fn(&Termsweeper) -> bool,
+}
+
+/// The lessons in order. Coordinates in the prompts are 1-based `(row,column)` like the
+/// cursor position in the header, the checks use zero-based locations.
+const STEPS: [Step; 5] = [
+    Step {
+        prompt: "Welcome! Press Space to reveal the field under the cursor. A first reveal \
+                 in the corner like this one is safe, and a field without mines around it \
+                 opens its neighbours too.",
+        done: |game| game.is_revealed((0, 0)),
+    },
+    Step {
+        prompt: "Numbers count the mines touching a field. Move with the arrow keys or \
+                 H J K L to the 1 at (2,1).",
+        done: |game| game.cursor_position() == (1, 0) || game.is_flagged((2, 0)),
+    },
+    Step {
+        prompt: "This 1 touches a single covered field, so (3,1) below it must be a mine. \
+                 Move there and flag it with M.",
+        done: |game| game.is_flagged((2, 0)),
+    },
+    Step {
+        prompt: "The 1 at (3,2) already touches its flagged mine, so its other covered \
+                 neighbour is safe. Press Space on the 1 to reveal around it at once.",
+        done: |game| game.is_revealed((3, 0)),
+    },
+    Step {
+        prompt: "That is all there is to it. Use the numbers to reveal every field without \
+                 a mine and win the board.",
+        done: Termsweeper::is_won,
+    },
+];
+
+/// A scripted game on [`LAYOUT`] that explains the rules step by step, advancing as the
+/// player performs each action. It plays by the real game logic, only the rules that change
+/// the lessons are fixed.
+pub struct Tutorial {
+    game: Termsweeper,
+    /// Index of the current lesson in [`STEPS`], its length once the board is won.
+    step: usize,
+}
+
+impl Tutorial {
+    pub fn new(settings: &Settings) -> Tutorial {
+        let settings = Settings {
+            neighbor_radius: 1,
+            flood_fill: FloodFillPolicy::default(),
+            max_flood_per_click: 0,
+            chord_on_reveal: true,
+            chord_on_flag: false,
+            lives: 1,
+            reveal_guard: 0,
+            show_cursor_position: true,
+            ..settings.clone()
+        };
+        let mut game =
+            Termsweeper::from_layout(LAYOUT, &settings).expect("the tutorial layout is valid");
+        game.set_cursor(0, 0);
+        Tutorial { game, step: 0 }
+    }
+
+    pub fn handle_event(&mut self, key: KeyEvent) -> bool {
+        let handled = self.game.handle_event(key);
+        while STEPS
+            .get(self.step)
+            .is_some_and(|step| (step.done)(&self.game))
+        {
+            self.step += 1;
+        }
+        handled
+    }
+
+    pub fn game(&self) -> &Termsweeper {
+        &self.game
+    }
+
+    pub fn game_mut(&mut self) -> &mut Termsweeper {
+        &mut self.game
+    }
+
+    fn prompt(&self) -> &'static str {
+        match self.game.outcome() {
+            Outcome::Won => {
+                "Well done, you cleared the board! Press E to go back to the menu and start a \
+                 real game."
+            }
+            Outcome::Lost => "That was a mine. Press R to start the tutorial over.",
+            Outcome::InProgress => STEPS.get(self.step).map_or("", |step| step.prompt),
+        }
+    }
+
+    /// The game screen with the prompt of the current lesson below it.
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(5)])
+            .split(area);
+        self.game.render_game_screen(layout[0], buf);
+        let title = format!(
+            " Tutorial - step {} of {} ",
+            (self.step + 1).min(STEPS.len()),
+            STEPS.len()
+        );
+        let block = Block::default()
+            .title(Title::from(title.yellow().bold()).alignment(Alignment::Center))
+            .borders(Borders::ALL)
+            .border_set(border::THICK);
+        Paragraph::new(self.prompt())
+            .wrap(Wrap { trim: true })
+            .block(block)
+            .render(layout[1], buf);
+    }
+}